@@ -26,7 +26,8 @@ impl ConsoleLogger {
         };
 
         unsafe {
-            _ = log::set_logger(&LOGGER).map(|()| log::set_max_level(level_filter));
+            _ = log::set_logger(&*std::ptr::addr_of!(LOGGER))
+                .map(|()| log::set_max_level(level_filter));
         }
     }
 }
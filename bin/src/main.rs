@@ -6,12 +6,17 @@ use dialoguer::Input;
 use log::{debug, info};
 use unitconvert::converter::builder::UnitConverterBuilder;
 use unitconvert::converter::error::ConversionError;
-use unitconvert::converter::UnitConverter;
+use unitconvert::converter::{CaseSensitivity, ParseMode, SignificantFigures, UnitConverter};
+use unitconvert::format::{Notation, ResultFormatter, UnitStyle};
+use unitconvert::source::csv::currency::ExchangeRateSourceCsv;
 use unitconvert::source::toml::conversions::BaseConversionsSourceToml;
+use unitconvert::source::toml::substances::SubstanceDefinitionSourceToml;
+use unitconvert::source::toml::timezones::TimeZoneAliasSourceToml;
+use unitconvert::source::toml::ucum::UcumDefinitionSourceToml;
 use unitconvert::source::toml::units::UnitDefinitionSourceToml;
 
 use crate::input::{generate_input_theme, InputHistory};
-use crate::options::CliOptions;
+use crate::options::{CaseMatching, CliOptions, ValueNotation};
 
 use self::logger::ConsoleLogger;
 
@@ -25,7 +30,8 @@ fn main() {
     debug!("Cli args: {:?}", cli);
 
     info!("Building unit converter object");
-    match build_converter() {
+    let formatter = build_formatter(&cli);
+    match build_converter(&cli) {
         Ok(mut converter) => {
             if cli.interactive {
                 let mut history = InputHistory::default();
@@ -36,7 +42,7 @@ fn main() {
                         .history_with(&mut history)
                         .interact_text()
                     {
-                        process_cmd(&mut converter, &cmd);
+                        process_cmd(&mut converter, &formatter, &cmd);
                     }
                 }
             } else {
@@ -49,7 +55,7 @@ fn main() {
                                 return;
                             } else {
                                 let command = remove_new_line_characters(&input);
-                                process_cmd(&mut converter, command);
+                                process_cmd(&mut converter, &formatter, command);
                             }
                         }
                         Err(error) => {
@@ -66,19 +72,75 @@ fn main() {
     }
 }
 
-fn build_converter() -> Result<UnitConverter, ConversionError> {
+fn build_converter(cli: &CliOptions) -> Result<UnitConverter, ConversionError> {
     let conversions = BaseConversionsSourceToml::new("Base_Conversions.toml").load()?;
     let units = UnitDefinitionSourceToml::new("Units.toml").load()?;
+    let ucum_codes = UcumDefinitionSourceToml::new("Ucum.toml").load()?;
+    let substances = SubstanceDefinitionSourceToml::new("Substances.toml").load()?;
+    let exchange_rates = ExchangeRateSourceCsv::new("Exchange_Rates.csv").load()?;
+    let time_zone_aliases = TimeZoneAliasSourceToml::new("Time_Zones.toml").load()?;
 
-    UnitConverterBuilder::new()
+    let parse_mode = match cli.ucum {
+        true => ParseMode::Ucum,
+        false => ParseMode::Abbreviation,
+    };
+
+    let case_sensitivity = match cli.case {
+        CaseMatching::Exact => CaseSensitivity::Exact,
+        CaseMatching::Unambiguous => CaseSensitivity::InsensitiveWhenUnambiguous,
+        CaseMatching::Insensitive => CaseSensitivity::Insensitive,
+    };
+
+    let significant_figures = match cli.sig_figs {
+        None => SignificantFigures::Off,
+        Some(None) => SignificantFigures::Inferred,
+        Some(Some(figures)) => SignificantFigures::Fixed(figures),
+    };
+
+    let mut builder = UnitConverterBuilder::new()
         .reverse_base_conversions(true)
         .cache_results(true)
+        .parse_mode(parse_mode)
+        .warn_ambiguous_units(cli.warn_ambiguous)
+        .exact_arithmetic(cli.exact)
+        .default_region(&cli.region)
+        .case_sensitivity(case_sensitivity)
+        .significant_figures(significant_figures)
         .add_unit_definitions(units)
         .add_base_conversions(conversions)
-        .build()
+        .add_ucum_codes(ucum_codes)
+        .add_substances(substances)
+        .add_exchange_rates(exchange_rates)
+        .add_time_zone_aliases(time_zone_aliases);
+    for (name, value) in &cli.params {
+        builder = builder.parameter(name, *value);
+    }
+    builder.build()
+}
+
+fn build_formatter(cli: &CliOptions) -> ResultFormatter {
+    let notation = match cli.notation {
+        ValueNotation::Auto => Notation::Auto,
+        ValueNotation::Fixed => Notation::Fixed,
+        ValueNotation::Scientific => Notation::Scientific,
+        ValueNotation::Engineering => Notation::Engineering,
+    };
+    let unit_style = match cli.symbols {
+        true => UnitStyle::Symbol,
+        false => UnitStyle::Name,
+    };
+
+    let mut formatter = ResultFormatter::new()
+        .notation(notation)
+        .unit_style(unit_style)
+        .auto_scale(cli.auto_scale);
+    if let Some(precision) = cli.precision {
+        formatter = formatter.precision(precision);
+    }
+    formatter
 }
 
-fn process_cmd(converter: &mut UnitConverter, cmd: &str) {
+fn process_cmd(converter: &mut UnitConverter, formatter: &ResultFormatter, cmd: &str) {
     if cmd == "exit" {
         process::exit(0);
     } else if cmd == "units" {
@@ -100,18 +162,49 @@ fn process_cmd(converter: &mut UnitConverter, cmd: &str) {
 
         match converter.convert_from_expression(cmd) {
             Ok(result) => {
-                if result.value > 99999.0 || result.value < 0.00009 {
+                for warning in &result.warnings {
+                    eprintln!(
+                        "{} {}",
+                        style(format!("{: <5}", "WARN")).fg(Color::Yellow).bold(),
+                        warning
+                    );
+                }
+                if result.approximate {
+                    eprintln!(
+                        "{} Result couldn't be calculated exactly",
+                        style(format!("{: <5}", "WARN")).fg(Color::Yellow).bold(),
+                    );
+                }
+
+                if let Some(time) = result.time {
                     println!(
-                        "{:e} {}",
-                        style(result.value).fg(console::Color::White).bold(),
-                        result.to.to_lowercase()
-                    )
+                        "{} {}",
+                        style(time.format("%Y-%m-%d %H:%M")).fg(Color::White).bold(),
+                        result.to
+                    );
+                } else if !result.components.is_empty() && result.dms.is_none() {
+                    let mut components = result
+                        .components
+                        .iter()
+                        .enumerate()
+                        .filter(|(i, c)| c.value != 0.0 || *i == result.components.len() - 1)
+                        .map(|(_, c)| format!("{} {}", style(c.value).bold(), c.abbrev))
+                        .collect::<Vec<_>>();
+                    // The uncertainty is in the first unit (i.e. "(1.04237 ± 0.00006 d)").
+                    if let Some(text) = &result.formatted_uncertainty {
+                        components.push(format!("({} {})", text, result.components[0].abbrev));
+                    }
+                    println!("{}", components.join(" "));
                 } else {
+                    let formatted = formatter.format(&result, converter.units());
                     println!(
                         "{} {}",
-                        style(result.value).fg(console::Color::White).bold(),
-                        result.to.to_lowercase()
-                    )
+                        style(formatted.value).fg(Color::White).bold(),
+                        formatted.unit
+                    );
+                }
+                if let Some(date) = result.date {
+                    println!("Rate from {}", style(date).italic());
                 }
             }
             Err(err) => eprintln!(
@@ -138,7 +231,7 @@ fn display_converter_units(converter: &UnitConverter) {
     }
 }
 
-fn remove_new_line_characters(input: &String) -> &str {
+fn remove_new_line_characters(input: &str) -> &str {
     input
         .strip_suffix("\r\n")
         .or(input.strip_suffix('\n'))
@@ -7,6 +7,82 @@ pub struct CliOptions {
 
     #[arg(short, long, default_value_t = true)]
     pub interactive: bool,
+
+    #[arg(long)]
+    pub ucum: bool,
+
+    /// Warns when using decimal units that are often confused with binary units (i.e. GB).
+    #[arg(long)]
+    pub warn_ambiguous: bool,
+
+    /// Calculates conversions using exact fractions rather than floating point where possible.
+    #[arg(long)]
+    pub exact: bool,
+
+    /// Rounds results to significant figures, inferred from the value being converted unless a
+    /// number is given.
+    #[arg(long, value_name = "N", num_args = 0..=1)]
+    pub sig_figs: Option<Option<u32>>,
+
+    /// How result values are written.
+    #[arg(long, value_enum, default_value_t = ValueNotation::Auto)]
+    pub notation: ValueNotation,
+
+    /// The number of decimal places of result values.
+    #[arg(long)]
+    pub precision: Option<usize>,
+
+    /// Writes result units as abbreviations rather than names.
+    #[arg(long)]
+    pub symbols: bool,
+
+    /// Writes results using the SI prefixed unit that gives the most readable value.
+    #[arg(long)]
+    pub auto_scale: bool,
+
+    /// How abbreviations are matched against units.
+    #[arg(long, value_enum, default_value_t = CaseMatching::Exact)]
+    pub case: CaseMatching,
+
+    /// The region used for units with regional variants (i.e. "us" or "imp" for gallons).
+    #[arg(long, default_value = "us")]
+    pub region: String,
+
+    /// Sets a parameter used by conversions (i.e. dpi=300).
+    #[arg(long = "param", value_name = "NAME=VALUE", value_parser = parse_param)]
+    pub params: Vec<(String, f64)>,
+}
+
+fn parse_param(input: &str) -> Result<(String, f64), String> {
+    let (name, value) = input
+        .split_once('=')
+        .ok_or(format!("expected NAME=VALUE but found '{}'", input))?;
+    let value = value
+        .trim()
+        .parse::<f64>()
+        .map_err(|err| format!("invalid value for parameter '{}': {}", name, err))?;
+    Ok((name.trim().to_owned(), value))
+}
+
+#[derive(ValueEnum, Clone, Debug)]
+pub enum CaseMatching {
+    /// Abbreviations must match exactly.
+    Exact,
+    /// Abbreviations are matched ignoring case when there is no exact match, unless that is
+    /// ambiguous.
+    Unambiguous,
+    /// Abbreviations are always matched ignoring case.
+    Insensitive,
+}
+
+#[derive(ValueEnum, Clone, Debug)]
+pub enum ValueNotation {
+    /// Fixed notation, or scientific notation for very large or small values.
+    Auto,
+    Fixed,
+    Scientific,
+    /// Scientific notation with exponents that are multiples of 3.
+    Engineering,
 }
 
 #[derive(ValueEnum, Clone, Debug)]
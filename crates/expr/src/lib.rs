@@ -74,6 +74,13 @@ pub enum Function {
     Sin,
     Cos,
     Tan,
+    /// Trigonometric functions that take an angle in degrees rather than radians.
+    SinDeg,
+    CosDeg,
+    TanDeg,
+    Log,
+    Ln,
+    Exp,
 }
 
 pub fn eval(input: &str) -> Result<f64, ExpressionError> {
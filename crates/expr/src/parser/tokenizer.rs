@@ -90,8 +90,9 @@ fn number(input: &str) -> Result<(Token, usize), ParseError> {
 fn identifier(input: &str) -> Result<(Token, usize), ParseError> {
     let mut end_pos: usize = input.len();
 
+    // Identifiers start with a letter, but can contain digits and underscores after that.
     for (pos, c) in input.char_indices() {
-        if !c.is_alphabetic() {
+        if !(c.is_alphabetic() || (pos > 0 && (c.is_ascii_digit() || c == '_'))) {
             end_pos = pos;
             break;
         }
@@ -116,6 +117,12 @@ fn func(input: &str) -> Option<Token> {
         "sin" => Some(Token::Func(Function::Sin)),
         "cos" => Some(Token::Func(Function::Cos)),
         "tan" => Some(Token::Func(Function::Tan)),
+        "sind" => Some(Token::Func(Function::SinDeg)),
+        "cosd" => Some(Token::Func(Function::CosDeg)),
+        "tand" => Some(Token::Func(Function::TanDeg)),
+        "log" => Some(Token::Func(Function::Log)),
+        "ln" => Some(Token::Func(Function::Ln)),
+        "exp" => Some(Token::Func(Function::Exp)),
         _ => None,
     }
 }
@@ -232,6 +239,17 @@ mod tests {
         assert_eq!(expected, actual.unwrap());
     }
 
+    #[test]
+    fn parameter_with_underscore() {
+        let expected = vec![
+            Token::Parameter(String::from("em")),
+            Token::Operator(Operator::Multiplication),
+            Token::Parameter(String::from("font_size2")),
+        ];
+        let actual = parse("em * font_size2");
+        assert_eq!(expected, actual.unwrap());
+    }
+
     #[test]
     fn decimal() {
         let expected = vec![
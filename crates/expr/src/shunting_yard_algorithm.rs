@@ -51,6 +51,12 @@ pub fn eval_ast(node: &AbstractSyntaxTreeNode, ctx: &impl ExpressionContext) ->
                 Function::Sin => expr_result.sin(),
                 Function::Cos => expr_result.cos(),
                 Function::Tan => expr_result.tan(),
+                Function::SinDeg => expr_result.to_radians().sin(),
+                Function::CosDeg => expr_result.to_radians().cos(),
+                Function::TanDeg => expr_result.to_radians().tan(),
+                Function::Log => expr_result.log10(),
+                Function::Ln => expr_result.ln(),
+                Function::Exp => expr_result.exp(),
                 _ => unreachable!(),
             };
             debug!(
@@ -72,6 +78,25 @@ pub fn eval_ast(node: &AbstractSyntaxTreeNode, ctx: &impl ExpressionContext) ->
     }
 }
 
+fn pop_function(func: Function, start: usize, output: &mut Vec<AbstractSyntaxTreeNode>) {
+    // Parameters are taken from the end of the output queue, so are in reverse order.
+    let mut params: Vec<_> = output.drain(start.min(output.len())..).rev().collect();
+    debug!(
+        "Popping {} parameter(s) from output queue for function {:?}",
+        params.len(),
+        func
+    );
+
+    match params.len() {
+        2.. => output.push(AbstractSyntaxTreeNode::FunctionParams { func, params }),
+        1 => output.push(AbstractSyntaxTreeNode::FunctionExpression {
+            func,
+            expr: Box::new(params.remove(0)),
+        }),
+        0 => {}
+    }
+}
+
 fn pop_to_output_queue(token: Token, output: &mut Vec<AbstractSyntaxTreeNode>) {
     debug!("Popping operator {:?} from stack to output queue", token);
     trace!("{} items in output queue {:?}", output.len(), output);
@@ -83,49 +108,6 @@ fn pop_to_output_queue(token: Token, output: &mut Vec<AbstractSyntaxTreeNode>) {
         Token::Parameter(var) => {
             output.push(AbstractSyntaxTreeNode::Variable(var));
         }
-        Token::Func(func) => {
-            // TODO: Rewrite this code to remove unwrap() functions.
-            // TODO: Fix error where when the function is the right side of a binary expression,
-            // the left hand side is evaluated as a part of the fucntion parameters.
-            let mut params = vec![];
-            while let Some(param) = output.last() {
-                match param {
-                    AbstractSyntaxTreeNode::Number(num) => {
-                        debug!(
-                            "Popping number {:?} from output queue to function parameters.",
-                            num
-                        );
-                        params.push(output.pop().unwrap());
-                    }
-                    AbstractSyntaxTreeNode::Variable(_) => params.push(output.pop().unwrap()),
-                    AbstractSyntaxTreeNode::UnaryExpression { operator, value } => {
-                        params.push(output.pop().unwrap())
-                    }
-                    AbstractSyntaxTreeNode::BinaryExpression {
-                        operator,
-                        left,
-                        right,
-                    } => params.push(output.pop().unwrap()),
-                    _ => break,
-                };
-            }
-
-            match params.len() {
-                2.. => output.push(AbstractSyntaxTreeNode::FunctionParams { func, params }),
-                1 => output.push(AbstractSyntaxTreeNode::FunctionExpression {
-                    func,
-                    expr: Box::new(params.remove(0)),
-                }),
-                0 => {
-                    if let Some(expr) = output.pop() {
-                        output.push(AbstractSyntaxTreeNode::FunctionExpression {
-                            func,
-                            expr: Box::new(expr),
-                        })
-                    }
-                }
-            }
-        }
         Token::Operator(Operator::Negative) => {
             if let Some(val) = output.pop() {
                 output.push(AbstractSyntaxTreeNode::UnaryExpression {
@@ -155,6 +137,8 @@ fn pop_to_output_queue(token: Token, output: &mut Vec<AbstractSyntaxTreeNode>) {
 pub fn shunting_yard(tokens: Vec<Token>) -> Result<AbstractSyntaxTreeNode, ExpressionError> {
     let mut output = Vec::with_capacity(tokens.len());
     let mut stack: Vec<Token> = Vec::with_capacity(tokens.len());
+    // Position in the output queue where the parameters of each function on the stack start.
+    let mut func_params: Vec<usize> = vec![];
 
     for token in tokens {
         trace!("Analyising token {:?}", token);
@@ -166,6 +150,7 @@ pub fn shunting_yard(tokens: Vec<Token>) -> Result<AbstractSyntaxTreeNode, Expre
                 output.push(AbstractSyntaxTreeNode::Variable(var));
             }
             Token::Func(_) => {
+                func_params.push(output.len());
                 stack.push(token);
             }
             Token::Operator(o1) => {
@@ -202,8 +187,10 @@ pub fn shunting_yard(tokens: Vec<Token>) -> Result<AbstractSyntaxTreeNode, Expre
                     if *top == Token::Left {
                         _ = stack.pop();
 
-                        if let Some(Token::Func(_)) = stack.last() {
-                            pop_to_output_queue(stack.pop().unwrap(), &mut output);
+                        if let Some(Token::Func(func)) = stack.last() {
+                            let func = *func;
+                            _ = stack.pop();
+                            pop_function(func, func_params.pop().unwrap_or(0), &mut output);
                         }
 
                         break;
@@ -221,7 +208,14 @@ pub fn shunting_yard(tokens: Vec<Token>) -> Result<AbstractSyntaxTreeNode, Expre
         if operator == Token::Left || operator == Token::Right {
             return Err(ExpressionError::new("Mismatched parentheses"));
         }
-        pop_to_output_queue(operator, &mut output);
+        match operator {
+            Token::Func(func) => pop_function(func, func_params.pop().unwrap_or(0), &mut output),
+            _ => pop_to_output_queue(operator, &mut output),
+        }
+    }
+
+    if output.is_empty() {
+        return Err(ExpressionError::new("Expression is empty"));
     }
 
     if output.len() > 1 {
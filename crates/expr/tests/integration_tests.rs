@@ -36,7 +36,14 @@ pub fn expression_no_parameters(exp: &str, expected: f64) {
 #[test_case("min(5, 8, -2)",          -2.0  ; "min_function_simple")]
 #[test_case("min(5, -2.3, 0 - 2)",    -2.3  ; "min_function_with_negative_decimal_and_expression_parameters")]
 #[test_case("max(5 - 2, 6 + 7) * 12", 156.0 ; "max_function_following_by_expression")]
-//#[test_case("12 * max(5 - 2, 6 + 7)", 156.0 ; "max_function_preceded_by_expression")]
+#[test_case("12 * max(5 - 2, 6 + 7)", 156.0 ; "max_function_preceded_by_expression")]
+#[test_case("10 * log(1000)",         30.0  ; "log_function_preceded_by_expression")]
+#[test_case("ln(exp(2))",             2.0   ; "ln_of_exp_function")]
+#[test_case("10^(30 / 10) / 1000",    1.0   ; "decibel_exponent")]
+#[test_case("exp(0) + log(100)",      3.0   ; "exp_and_log_functions")]
+#[test_case("sind(90)",               1.0   ; "sin_in_degrees")]
+#[test_case("2 * cosd(180)",          -2.0  ; "cos_in_degrees")]
+#[test_case("tand(0) + sin(0)",       0.0   ; "tan_in_degrees")]
 pub fn expression_functions(expr: &str, expected: f64) {
     setup_test_logger();
     let actual = eval(expr);
@@ -0,0 +1,103 @@
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::{one_of, space0, space1},
+    combinator::{eof, map, opt, peek},
+    sequence::{preceded, terminated, tuple},
+    IResult,
+};
+
+use crate::parser::parse_number;
+
+/// The abbreviation of the unit that parsed DMS angles are given in.
+pub const DMS_UNIT: &str = "deg";
+/// The target that expands to the units rendered as degrees, minutes and seconds.
+pub const DMS_TARGET: &str = "dms";
+pub const DMS_UNITS: [&str; 3] = ["deg", "arcmin", "arcsec"];
+
+/// Parses an angle in degrees, minutes and seconds into degrees, either with symbols (i.e.
+/// 45°30'15") or as numbers separated by spaces followed by a hemisphere (i.e. "45 30 15 N").
+/// Angles in the southern or western hemispheres are negative.
+pub fn parse_dms(input: &str) -> IResult<&str, f64> {
+    let symbols = tuple((
+        terminated(parse_number, tag("°")),
+        opt(preceded(space0, terminated(parse_number, one_of("'′")))),
+        opt(preceded(
+            space0,
+            terminated(parse_number, alt((tag("\""), tag("″"), tag("''")))),
+        )),
+        opt(preceded(space0, parse_hemisphere)),
+    ));
+    let spaces = tuple((
+        parse_number,
+        map(preceded(space1, parse_number), Some),
+        opt(preceded(space1, parse_number)),
+        map(preceded(space1, parse_hemisphere), Some),
+    ));
+
+    let (rest, (degrees, minutes, seconds, hemisphere)) = alt((symbols, spaces))(input)?;
+    let magnitude =
+        degrees.abs() + minutes.unwrap_or_default() / 60.0 + seconds.unwrap_or_default() / 3600.0;
+    let negative = degrees.is_sign_negative() ^ matches!(hemisphere, Some('S' | 'W'));
+    Ok((rest, if negative { -magnitude } else { magnitude }))
+}
+
+/// Parses a hemisphere, which can't be followed by anything other than whitespace or an
+/// operator so that it isn't confused with a unit (i.e. "Nm").
+fn parse_hemisphere(input: &str) -> IResult<&str, char> {
+    terminated(one_of("NSEW"), peek(alt((eof, space1, tag("->")))))(input)
+}
+
+/// Formats an angle in degrees as degrees, minutes and seconds (i.e. 45°30'15"), with the seconds
+/// rounded to 6 decimal places.
+pub fn format_dms(degrees: f64) -> String {
+    let total = (degrees.abs() * 3600.0 * 1e6).round() / 1e6;
+    let whole = total.trunc() as u64;
+    let seconds = ((whole % 60) as f64 + total.fract()) * 1e6;
+    let sign = if degrees < 0.0 && total > 0.0 {
+        "-"
+    } else {
+        ""
+    };
+    format!(
+        "{}{}°{}'{}\"",
+        sign,
+        whole / 3600,
+        whole % 3600 / 60,
+        seconds.round() / 1e6
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dms() {
+        for (input, expected) in [
+            ("45°30'15\"", 45.50416666666667),
+            ("45° 30′ 15″", 45.50416666666667),
+            ("45°30'15''S", -45.50416666666667),
+            ("-45°30'", -45.5),
+            ("90°", 90.0),
+            ("45 30 15 N", 45.50416666666667),
+            ("122 30 W", -122.5),
+        ] {
+            assert_eq!(Ok(("", expected)), parse_dms(input), "{}", input);
+        }
+    }
+
+    #[test]
+    fn not_dms() {
+        for input in ["45 m", "45 30", "45 30 Nm", "2 cups of flour"] {
+            assert!(parse_dms(input).is_err(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn format() {
+        assert_eq!("45°30'15\"", format_dms(45.50416666666667));
+        assert_eq!("-122°30'0\"", format_dms(-122.5));
+        assert_eq!("57°17'44.806247\"", format_dms(1f64.to_degrees()));
+    }
+}
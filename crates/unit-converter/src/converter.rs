@@ -1,5 +1,24 @@
-use crate::graph::Graph;
-use crate::parser::{parse_conversion, UnitAbbreviation};
+use std::fmt::Display;
+
+use crate::angle::{format_dms, DMS_UNITS};
+use crate::currency::{ExchangeRates, CURRENCY_UNIT_TYPE};
+use crate::data::ambiguous_unit_warning;
+use crate::duration::split_components;
+use crate::graph::{EdgeIndex, Graph};
+use crate::log_scale::LogScale;
+use crate::numeral::{NumeralSystem, NUMERAL_UNIT_TYPE};
+use crate::parser::{
+    find_unit, parse_conversion, parse_numeral_expression, parse_time_expression,
+    AbbreviationCollision, ParsedNumeralExpression, ParsedTimeExpression, UnitAbbreviation,
+};
+use crate::precision::{round_to_significant_figures, Precision};
+use crate::rational::Rational;
+use crate::substance::SubstanceRegistry;
+use crate::table::{Interpolation, LookupTable};
+use crate::timezone::{convert_time, resolve_time_zone, TimeZoneAlias, TIME_ZONE_UNIT_TYPE};
+use crate::ucum::{parse_ucum_conversion, UcumCode};
+use crate::uncertainty::{format_uncertainty, linearize, propagate_interval};
+use chrono::{Local, NaiveDate, NaiveDateTime, TimeDelta, Timelike};
 use expr::expression::ExpressionContext;
 use expr::expression::{Expression, InMemoryExpressionContext};
 use log::{debug, error, info, warn};
@@ -9,26 +28,174 @@ use self::error::ConversionError;
 
 pub mod builder;
 pub mod error;
+mod export;
+pub mod watcher;
 
 // TODO: Give these structs more unique names, rather than them all being some variation of
 // Converter/Conversion.
+/// Multipliers and reciprocals also store their precision, which is either their exact value as
+/// a fraction, used to combine them without rounding errors in exact mode, or the number of
+/// significant figures of a rounded multiplier.
 pub enum Conversion {
-    Multiplier(f64),
+    Multiplier(f64, Precision),
+    Reciprocal(f64, Precision),
     Expression(Expression),
+    Table(LookupTable),
+    Logarithmic(LogScale),
+}
+
+impl Conversion {
+    /// The estimated relative error of the conversion, which is used to prefer paths made up of
+    /// exact conversions. Only rounded multipliers are known to be inexact.
+    fn relative_error(&self) -> f64 {
+        match self {
+            Conversion::Multiplier(_, precision) | Conversion::Reciprocal(_, precision) => {
+                precision.relative_error()
+            }
+            _ => 0.0,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum ParseMode {
+    #[default]
+    Abbreviation,
+    Ucum,
+}
+
+/// The region used to resolve abbreviations shared by regional variants of a unit by default.
+pub const DEFAULT_REGION: &str = "us";
+
+/// How abbreviations are matched against the units in an expression.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum CaseSensitivity {
+    /// Abbreviations must match exactly (i.e. "km" but not "KM").
+    #[default]
+    Exact,
+    /// Abbreviations that don't match exactly are matched ignoring case, unless that matches
+    /// several abbreviations (i.e. "MM" for both "Mm" and "mm").
+    InsensitiveWhenUnambiguous,
+    /// Abbreviations are always matched ignoring case, so abbreviations that only differ by case
+    /// are ambiguous.
+    Insensitive,
+}
+
+impl CaseSensitivity {
+    pub(crate) fn matches(&self, expected: &str, input: &str) -> bool {
+        match self {
+            CaseSensitivity::Exact => expected == input,
+            _ => expected.to_lowercase() == input.to_lowercase(),
+        }
+    }
+}
+
+/// How results are rounded to significant figures. Results are also limited to the significant
+/// figures of any rounded multipliers used by the conversion (i.e. 39.3701 inches per meter).
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum SignificantFigures {
+    /// Results aren't rounded.
+    #[default]
+    Off,
+    /// Results have as many significant figures as the value being converted (i.e. 1 for "2 km"
+    /// but 4 for "2.000 km").
+    Inferred,
+    /// Results are rounded to a fixed number of significant figures.
+    Fixed(u32),
 }
 
 pub struct UnitConverter {
     graph: Vec<Graph<String, Conversion>>,
+    definition_edges: Vec<usize>,
+    /// The indices of the edges in each graph that were added by reversing a definition.
+    reversed_edges: Vec<Vec<EdgeIndex>>,
     abbreviations: Vec<UnitAbbreviation>,
+    /// The number of abbreviations that were defined, which come before those generated for
+    /// currencies and numeral systems.
+    definition_abbreviations: usize,
+    collisions: Vec<AbbreviationCollision>,
+    ucum_codes: Vec<UcumCode>,
+    substances: SubstanceRegistry,
+    exchange_rates: ExchangeRates,
+    max_rate_age: TimeDelta,
+    time_zone_aliases: Vec<TimeZoneAlias>,
+    region: String,
+    case_sensitivity: CaseSensitivity,
+    significant_figures: SignificantFigures,
+    parse_mode: ParseMode,
+    params: InMemoryExpressionContext,
+    warn_ambiguous_units: bool,
+    exact_arithmetic: bool,
     cache: bool,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Default)]
 pub struct UnitConversion {
     pub value: f64,
     pub from: String,
     pub to: String,
     pub unit_type: String,
+    /// The type of the target unit, when converting between unit types using a substance.
+    pub to_type: Option<String>,
+    pub to_ucum: Option<String>,
+    pub substance: Option<String>,
+    /// The date of the exchange rate used for a currency conversion.
+    pub date: Option<NaiveDate>,
+    /// The converted wall-clock time for a time zone conversion, where the value is the time of
+    /// day in hours.
+    pub time: Option<NaiveDateTime>,
+    /// The exact result when it can't be represented by the value, for conversions between
+    /// numeral systems (i.e. "FF" for "255 dec -> hex").
+    pub exact: Option<ConversionValue>,
+    /// Whether the result was calculated using floating point arithmetic in exact mode, because
+    /// the conversion uses an expression, table, logarithmic scale or substance, or a multiplier
+    /// that can't be represented as a fraction.
+    pub approximate: bool,
+    /// The absolute uncertainty of the value, when the value being converted had one.
+    pub uncertainty: Option<f64>,
+    /// The value with its uncertainty, rounded to the precision of the uncertainty (i.e. "4.92 ±
+    /// 0.08").
+    pub formatted_uncertainty: Option<String>,
+    /// The result as degrees, minutes and seconds (i.e. 45°30'15"), when converting to "dms".
+    pub dms: Option<String>,
+    /// The number of significant figures of the result, when rounding to significant figures.
+    /// When parsing, the number of significant figures of the value being converted.
+    pub significant_figures: Option<u32>,
+    /// The value rounded to its significant figures.
+    pub rounded: Option<f64>,
+    /// The estimated relative error of the result caused by rounded multipliers, which is the
+    /// sum of their relative errors (i.e. 5e-6 for 39.3701 inches per meter).
+    pub relative_error: f64,
+    /// The result split into whole amounts of each target unit, with any remainder in the last
+    /// unit (i.e. "90061 s -> d h min s"). Empty unless several target units were given.
+    pub components: Vec<UnitComponent>,
+    pub warnings: Vec<String>,
+}
+
+/// A value that can't be represented exactly as a float, such as an integer too large for an
+/// f64 or a number in a non-decimal numeral system.
+#[derive(Debug, PartialEq, Clone)]
+pub enum ConversionValue {
+    Float(f64),
+    Integer(i128),
+    Text(String),
+}
+
+impl Display for ConversionValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConversionValue::Float(value) => write!(f, "{}", value),
+            ConversionValue::Integer(value) => write!(f, "{}", value),
+            ConversionValue::Text(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct UnitComponent {
+    pub value: f64,
+    pub unit: String,
+    pub abbrev: String,
 }
 
 impl UnitConverter {
@@ -41,47 +208,342 @@ impl UnitConverter {
         abbreviations: Vec<UnitAbbreviation>,
         cache: bool,
     ) -> UnitConverter {
+        // Any edges added after this point are cached conversions rather than definitions.
+        let definition_edges = graph.iter().map(|g| g.edge_count()).collect();
         UnitConverter {
             graph,
+            definition_edges,
+            reversed_edges: vec![],
+            definition_abbreviations: abbreviations.len(),
             abbreviations,
+            collisions: vec![],
+            ucum_codes: vec![],
+            substances: SubstanceRegistry::default(),
+            exchange_rates: ExchangeRates::default(),
+            max_rate_age: TimeDelta::days(7),
+            time_zone_aliases: vec![],
+            region: String::from(DEFAULT_REGION),
+            case_sensitivity: CaseSensitivity::default(),
+            significant_figures: SignificantFigures::default(),
+            parse_mode: ParseMode::default(),
+            params: InMemoryExpressionContext::default(),
+            warn_ambiguous_units: false,
+            exact_arithmetic: false,
             cache,
         }
     }
 
+    /// Finds a unit by its name, ignoring case, or by its abbreviation using the converter's case
+    /// sensitivity.
     pub fn unit_info(&self, input: &str) -> Result<UnitAbbreviation, ConversionError> {
         let lc_input = input.to_lowercase();
-        for abbrev in &self.abbreviations {
-            if abbrev.unit.to_lowercase() == lc_input || abbrev.abbrev == lc_input {
-                return Ok(abbrev.to_owned());
-            }
+        if let Some(unit) = self
+            .abbreviations
+            .iter()
+            .find(|u| u.unit.to_lowercase() == lc_input)
+        {
+            return Ok(unit.to_owned());
         }
+        find_unit(&self.abbreviations, input, self.case_sensitivity).map(|u| u.to_owned())
+    }
 
-        // TODO: Return an actual error here rather than just default().
-        Err(ConversionError::default())
+    /// Abbreviations used by units of several types, which are resolved using the other unit in a
+    /// conversion or can be qualified with the unit type (i.e. "Capacity:pt").
+    pub fn abbreviation_collisions(&self) -> &[AbbreviationCollision] {
+        &self.collisions
+    }
+
+    /// Sets the region used to resolve abbreviations shared by regional variants of a unit (i.e.
+    /// "us" or "imp" for "gal") for all future conversions.
+    pub fn set_region(&mut self, region: &str) {
+        self.region = region.to_owned();
+    }
+
+    /// Sets the value of a parameter used by conversion expressions for all future conversions.
+    pub fn set_parameter(&mut self, name: &str, value: f64) {
+        self.params.var(name, value);
     }
 
     pub fn convert_from_expression(
         &mut self,
         input: &str,
     ) -> Result<UnitConversion, ConversionError> {
-        match parse_conversion(&self.abbreviations, input) {
-            Ok(conversion) => {
-                info!("Parsed {:?}", conversion);
-                let result = self.convert_from_definition(
+        self.convert_from_expression_with_ctx(input, &InMemoryExpressionContext::default())
+    }
+
+    /// Converts using the parameters in `ctx`, which take precedence over the parameters set on
+    /// the converter and the defaults in the definitions.
+    pub fn convert_from_expression_with_ctx(
+        &mut self,
+        input: &str,
+        ctx: &impl ExpressionContext,
+    ) -> Result<UnitConversion, ConversionError> {
+        if let Some(expression) = parse_time_expression(input)? {
+            return self.convert_time_zone(expression);
+        }
+        if let Some(expression) = parse_numeral_expression(input) {
+            if let Some(to) = NumeralSystem::from_abbrev(expression.to) {
+                return convert_numeral(expression, to);
+            }
+        }
+
+        let (mut conversion, scale) = match self.parse_mode {
+            ParseMode::Abbreviation => (
+                parse_conversion(
+                    &self.abbreviations,
+                    input,
+                    &self.region,
+                    self.case_sensitivity,
+                )?,
+                1.0,
+            ),
+            ParseMode::Ucum => parse_ucum_conversion(&self.ucum_codes, input)?,
+        };
+        info!("Parsed {:?}", conversion);
+
+        let mut warnings = std::mem::take(&mut conversion.warnings);
+        let mut date = None;
+        if conversion.date.is_some() && conversion.unit_type != CURRENCY_UNIT_TYPE {
+            warnings.push(String::from(
+                "Dates are only used when converting between currencies",
+            ));
+        }
+        // A substance only changes the result when converting between unit types, but is still
+        // checked so a mistyped or unparsed phrase (i.e. "5 m of garbage -> ft") isn't ignored.
+        if let (Some(substance), None) = (&conversion.substance, &conversion.to_type) {
+            if self.substances.substance(substance).is_none() {
+                return Err(ConversionError::new(&format!(
+                    "Unknown substance '{}'",
+                    substance
+                )));
+            }
+            warnings.push(String::from(
+                "Substances are only used when converting between unit types",
+            ));
+        }
+        if self.warn_ambiguous_units {
+            let units = [&conversion.from, &conversion.to];
+            let components = conversion.components.iter().map(|c| &c.unit);
+            for unit in units.into_iter().chain(components) {
+                if let Some(warning) = ambiguous_unit_warning(unit) {
+                    if !warnings.contains(&warning) {
+                        warnings.push(warning);
+                    }
+                }
+            }
+        }
+        let mut calculated_exactly = false;
+        let mut path_figures = None;
+        let mut relative_error = 0.0;
+        let mut uncertainty = conversion.uncertainty;
+        let result = match &conversion.to_type {
+            Some(to_type) => {
+                let result = self.convert_with_substance(&conversion, to_type, ctx)?;
+                path_figures = result.significant_figures;
+                relative_error = result.relative_error;
+                uncertainty = uncertainty.map(|_| result.uncertainty);
+                result.value
+            }
+            None if conversion.unit_type == CURRENCY_UNIT_TYPE
+                && !self.exchange_rates.is_empty() =>
+            {
+                let convert = |x| {
+                    self.exchange_rates.convert(
+                        &conversion.from,
+                        &conversion.to,
+                        x,
+                        conversion.date,
+                    )
+                };
+                let (result, rate_date) = convert(conversion.value)?;
+                if let Some(u) = uncertainty {
+                    let convert = |x| convert(x).map(|(result, _)| result);
+                    uncertainty = Some(linearize(convert, conversion.value, u)?);
+                }
+
+                // Historical conversions are only stale if the rates are old relative to the
+                // requested date.
+                let age = conversion.date.unwrap_or(Local::now().date_naive()) - rate_date;
+                if age > self.max_rate_age {
+                    let warning = format!(
+                        "Exchange rate from {} is {} days old",
+                        rate_date,
+                        age.num_days()
+                    );
+                    warn!("{}", warning);
+                    warnings.push(warning);
+                }
+                date = Some(rate_date);
+                result
+            }
+            None => {
+                let result = self.convert_along_path(
                     &conversion.unit_type,
                     &conversion.from,
                     &conversion.to,
                     conversion.value,
+                    conversion.uncertainty.unwrap_or_default(),
+                    ctx,
                 )?;
-                Ok(UnitConversion {
-                    value: result,
-                    from: conversion.from,
-                    to: conversion.to,
-                    unit_type: conversion.unit_type,
-                })
+                calculated_exactly = result.exact;
+                path_figures = result.significant_figures;
+                relative_error = result.relative_error;
+                uncertainty = uncertainty.map(|_| result.uncertainty);
+                result.value
+            }
+        };
+        let to_type = conversion.to_type.as_ref().unwrap_or(&conversion.unit_type);
+        let mut components = conversion.components;
+        if let Some(last) = components.last() {
+            let last = last.unit.to_owned();
+            let total =
+                self.convert_from_definition_with_ctx(to_type, &conversion.to, &last, result, ctx)?;
+            let factors = components
+                .iter()
+                .map(|c| self.convert_from_definition_with_ctx(to_type, &c.unit, &last, 1.0, ctx))
+                .collect::<Result<Vec<_>, _>>()?;
+            for (component, value) in components.iter_mut().zip(split_components(total, &factors)) {
+                component.value = value;
+            }
+        }
+        // Angles split into degrees, minutes and seconds are also given as text (i.e. 45°30'15").
+        let dms = components
+            .iter()
+            .map(|c| c.abbrev.as_str())
+            .eq(DMS_UNITS)
+            .then(|| format_dms(result));
+        let uncertainty = uncertainty.map(|u| u / scale);
+        let significant_figures = match self.significant_figures {
+            SignificantFigures::Off => None,
+            SignificantFigures::Inferred => {
+                min_figures(conversion.significant_figures, path_figures)
             }
-            Err(err) => Err(err),
+            SignificantFigures::Fixed(figures) => min_figures(Some(figures), path_figures),
+        };
+        let to_ucum = conversion.to_ucum.or_else(|| {
+            self.ucum_code(to_type, &conversion.to)
+                .map(|code| code.to_owned())
+        });
+
+        Ok(UnitConversion {
+            value: result / scale,
+            from: conversion.from,
+            to: conversion.to,
+            unit_type: conversion.unit_type,
+            to_type: conversion.to_type,
+            to_ucum,
+            substance: conversion.substance,
+            date,
+            time: None,
+            exact: None,
+            approximate: self.exact_arithmetic && !calculated_exactly,
+            uncertainty,
+            formatted_uncertainty: uncertainty.map(|u| format_uncertainty(result / scale, u)),
+            dms,
+            significant_figures,
+            rounded: significant_figures.map(|n| round_to_significant_figures(result / scale, n)),
+            relative_error,
+            components,
+            warnings,
+        })
+    }
+
+    /// Converts a wall-clock time between time zones, which are given as IANA names or aliases.
+    fn convert_time_zone(
+        &self,
+        expression: ParsedTimeExpression,
+    ) -> Result<UnitConversion, ConversionError> {
+        let mut warnings = vec![];
+        let from = resolve_time_zone(&self.time_zone_aliases, expression.from, &mut warnings)?;
+        let to = resolve_time_zone(&self.time_zone_aliases, expression.to, &mut warnings)?;
+        let mut result = convert_time(from, to, expression.time, expression.date)?;
+        warnings.append(&mut result.warnings);
+        for warning in &warnings {
+            warn!("{}", warning);
         }
+
+        Ok(UnitConversion {
+            value: result.time.num_seconds_from_midnight() as f64 / 3600.0,
+            from: from.name().to_owned(),
+            to: to.name().to_owned(),
+            unit_type: TIME_ZONE_UNIT_TYPE.to_owned(),
+            time: Some(result.time),
+            warnings,
+            ..Default::default()
+        })
+    }
+
+    /// Converts between units of different types by crossing the bridge between their graphs,
+    /// using the property of the substance that the bridge is based on. The conversions on either
+    /// side of the bridge follow the paths through each graph, so the result carries their error
+    /// and significant figures like a conversion within a single type.
+    fn convert_with_substance(
+        &mut self,
+        conversion: &UnitConversion,
+        to_type: &str,
+        ctx: &impl ExpressionContext,
+    ) -> Result<PathResult, ConversionError> {
+        let name = conversion
+            .substance
+            .as_deref()
+            .ok_or(ConversionError::new("Units are of different types"))?;
+        let substance = self
+            .substances
+            .substance(name)
+            .ok_or(ConversionError::new(&format!(
+                "Unknown substance '{}'",
+                name
+            )))?;
+        let (bridge, reversed) = self
+            .substances
+            .bridge(&conversion.unit_type, to_type)
+            .ok_or(ConversionError::new(&format!(
+                "Unable to convert between {} and {}",
+                conversion.unit_type, to_type
+            )))?;
+        let property = *substance
+            .properties
+            .get(&bridge.property)
+            .ok_or(ConversionError::new(&format!(
+                "No {} defined for {}",
+                bridge.property, substance.name
+            )))?;
+        debug!(
+            "Converting {} from {} to {} using {} {}",
+            substance.name, conversion.unit_type, to_type, bridge.property, property
+        );
+
+        let (source_unit, target_unit) = match reversed {
+            true => (bridge.to_unit.to_owned(), bridge.from_unit.to_owned()),
+            false => (bridge.from_unit.to_owned(), bridge.to_unit.to_owned()),
+        };
+        let first = self.convert_along_path(
+            &conversion.unit_type,
+            &conversion.from,
+            &source_unit,
+            conversion.value,
+            conversion.uncertainty.unwrap_or_default(),
+            ctx,
+        )?;
+        let apply = |x: f64| match reversed {
+            true => x / property,
+            false => x * property,
+        };
+        let second = self.convert_along_path(
+            to_type,
+            &target_unit,
+            &conversion.to,
+            apply(first.value),
+            apply(first.uncertainty).abs(),
+            ctx,
+        )?;
+        Ok(PathResult {
+            value: second.value,
+            uncertainty: second.uncertainty,
+            exact: false,
+            significant_figures: min_figures(first.significant_figures, second.significant_figures),
+            relative_error: first.relative_error + second.relative_error,
+        })
     }
 
     pub fn convert_from_definition(
@@ -91,14 +553,58 @@ impl UnitConverter {
         to: &str,
         value: f64,
     ) -> Result<f64, ConversionError> {
+        let ctx = InMemoryExpressionContext::default();
+        self.convert_from_definition_with_ctx(unit_type, from, to, value, &ctx)
+    }
+
+    pub fn convert_from_definition_with_ctx(
+        &mut self,
+        unit_type: &str,
+        from: &str,
+        to: &str,
+        value: f64,
+        ctx: &impl ExpressionContext,
+    ) -> Result<f64, ConversionError> {
+        self.convert_along_path(unit_type, from, to, value, 0.0, ctx)
+            .map(|result| result.value)
+    }
+
+    /// Converts a value and its uncertainty along the shortest path between two units. Results
+    /// are only exact in exact mode, when every conversion along the path is a multiplier or
+    /// reciprocal that can be represented as a fraction.
+    fn convert_along_path(
+        &mut self,
+        unit_type: &str,
+        from: &str,
+        to: &str,
+        value: f64,
+        uncertainty: f64,
+        ctx: &impl ExpressionContext,
+    ) -> Result<PathResult, ConversionError> {
         if let Some(graph_index) = self.get_graph_index(unit_type) {
             let n0 = self.get_graph_node_index(graph_index, from)?;
             let n1 = self.get_graph_node_index(graph_index, to)?;
+            if n0 == n1 {
+                return Ok(PathResult {
+                    value,
+                    uncertainty,
+                    exact: true,
+                    significant_figures: None,
+                    relative_error: 0.0,
+                });
+            }
 
-            let shortest_path = self.graph[graph_index].shortest_path(n0, n1);
+            // Paths are chosen to minimize the estimated error, so a longer chain of exact
+            // conversions is used rather than a rounded multiplier (i.e. 39.3701 inches per meter).
+            let shortest_path =
+                self.graph[graph_index].shortest_path(n0, n1, Conversion::relative_error);
             if shortest_path.is_empty() {
                 return Err(ConversionError::new("Unable to find conversion"));
             }
+            let relative_error = shortest_path
+                .iter()
+                .map(|edge| edge.weight.relative_error())
+                .sum();
 
             debug!(
                 "Converting from {} to {} will require {} operation(s)",
@@ -108,42 +614,152 @@ impl UnitConverter {
             );
 
             // TODO: Refactor this whole section of code.
+            // Consecutive multiplier and reciprocal conversions are combined into a single
+            // conversion of the form `multiplier * x`, or `multiplier / x` when inverted.
             let mut multiplier = 1.0;
+            let mut exact_multiplier = Some(Rational::ONE);
+            let mut figures = None;
+            let mut inverted = false;
             let mut result_val = value;
+            let mut result_uncertainty = uncertainty;
             let mut should_cache_multiplier: bool = true;
 
             for edge in &shortest_path {
                 match edge.weight {
-                    Conversion::Multiplier(val) => {
+                    Conversion::Multiplier(val, precision) => {
                         multiplier *= val;
+                        exact_multiplier = exact_multiplier
+                            .zip(precision.rational())
+                            .and_then(|(m, x)| m.checked_mul(x));
+                        figures = min_figures(figures, precision.significant_figures());
+                    }
+                    Conversion::Reciprocal(val, precision) => {
+                        multiplier = val / multiplier;
+                        exact_multiplier = exact_multiplier
+                            .and_then(|m| m.recip())
+                            .zip(precision.rational())
+                            .and_then(|(m, x)| m.checked_mul(x));
+                        figures = min_figures(figures, precision.significant_figures());
+                        inverted = !inverted;
                     }
                     Conversion::Expression(expr) => {
                         should_cache_multiplier = false;
-                        result_val *= multiplier;
+                        result_uncertainty = multiplier_uncertainty(
+                            multiplier,
+                            inverted,
+                            result_val,
+                            result_uncertainty,
+                        );
+                        result_val = apply_multiplier(multiplier, inverted, result_val)?;
                         multiplier = 1.0;
+                        inverted = false;
 
-                        let mut ctx = InMemoryExpressionContext::default();
-                        let params = self
+                        let abbrev = self
                             .get_unit_abbrev(edge.source, unit_type)
                             .ok_or(ConversionError::new("Unable to find unit."))?;
-                        ctx.var(params, result_val);
+                        let mut expr_ctx = InMemoryExpressionContext::default();
+                        for param in expr.params.iter().filter(|p| *p != abbrev) {
+                            let param_val = ctx
+                                .get(param)
+                                .or_else(|| self.params.get(param))
+                                .or_else(|| expr.ctx.get(param))
+                                .ok_or(ConversionError::new(&format!(
+                                    "Missing parameter '{}' for conversion from {} to {}",
+                                    param, edge.source, edge.target
+                                )))?;
+                            expr_ctx.var(param, param_val);
+                        }
+
+                        let mut eval = |x| {
+                            expr_ctx.var(abbrev, x);
+                            expr.eval_with_ctx(&expr_ctx).map_err(ConversionError::from)
+                        };
+                        result_uncertainty = linearize(&mut eval, result_val, result_uncertainty)?;
+                        result_val = eval(result_val)?;
+                    }
+                    Conversion::Table(table) => {
+                        should_cache_multiplier = false;
+                        result_uncertainty = multiplier_uncertainty(
+                            multiplier,
+                            inverted,
+                            result_val,
+                            result_uncertainty,
+                        );
+                        result_val = apply_multiplier(multiplier, inverted, result_val)?;
+                        multiplier = 1.0;
+                        inverted = false;
+
+                        // Step and nearest tables jump between values, so they have no useful
+                        // derivative.
+                        result_uncertainty = match table.interpolation {
+                            Interpolation::Linear => {
+                                linearize(|x| table.lookup(x), result_val, result_uncertainty)?
+                            }
+                            _ => propagate_interval(
+                                |x| table.lookup(x),
+                                result_val,
+                                result_uncertainty,
+                            )?,
+                        };
+                        result_val = table.lookup(result_val)?;
+                    }
+                    Conversion::Logarithmic(scale) => {
+                        should_cache_multiplier = false;
+                        result_uncertainty = multiplier_uncertainty(
+                            multiplier,
+                            inverted,
+                            result_val,
+                            result_uncertainty,
+                        );
+                        result_val = apply_multiplier(multiplier, inverted, result_val)?;
+                        multiplier = 1.0;
+                        inverted = false;
 
-                        result_val = expr.eval_with_ctx(&ctx)?;
+                        result_uncertainty =
+                            linearize(|x| scale.convert(x), result_val, result_uncertainty)?;
+                        result_val = scale.convert(result_val)?;
                     }
                 }
             }
 
-            result_val *= multiplier;
+            // In exact mode, paths made up of only multipliers and reciprocals are calculated
+            // using fractions and only converted to a float at the end.
+            let exact_result = match (
+                self.exact_arithmetic,
+                should_cache_multiplier,
+                exact_multiplier,
+            ) {
+                (true, true, Some(exact)) => {
+                    Rational::from_f64(value).and_then(|value| match inverted {
+                        true => value.recip().and_then(|v| exact.checked_mul(v)),
+                        false => exact.checked_mul(value),
+                    })
+                }
+                _ => None,
+            };
+            result_uncertainty =
+                multiplier_uncertainty(multiplier, inverted, result_val, result_uncertainty);
+            result_val = match exact_result {
+                Some(exact) => exact.to_f64(),
+                None => apply_multiplier(multiplier, inverted, result_val)?,
+            };
 
             // Should cache the multiplier only if all conversions were multiplier conversions and
             // if there length of the path is greater than 1.
             if self.cache && should_cache_multiplier && shortest_path.len() > 1 {
                 info!(
-                    "Caching conversion between {} and {} using multiplier {}",
-                    from, to, multiplier
+                    "Caching conversion between {} and {} using multiplier {} (inverted: {})",
+                    from, to, multiplier, inverted
                 );
-                let cache_result =
-                    self.graph[graph_index].add_edge(n0, n1, Conversion::Multiplier(multiplier));
+                let precision = match figures {
+                    Some(figures) => Precision::Combined(figures, relative_error),
+                    None => Precision::Exact(exact_multiplier),
+                };
+                let conversion = match inverted {
+                    true => Conversion::Reciprocal(multiplier, precision),
+                    false => Conversion::Multiplier(multiplier, precision),
+                };
+                let cache_result = self.graph[graph_index].add_edge(n0, n1, conversion);
                 if cache_result.is_err() {
                     warn!(
                         "Unable to add edge to graph between nodes {} and {}",
@@ -152,7 +768,13 @@ impl UnitConverter {
                 }
             }
 
-            return Ok(result_val);
+            return Ok(PathResult {
+                value: result_val,
+                uncertainty: result_uncertainty,
+                exact: exact_result.is_some(),
+                significant_figures: figures,
+                relative_error,
+            });
         }
 
         error!("Unable to get internal graph for unit type {}", unit_type);
@@ -161,10 +783,46 @@ impl UnitConverter {
         ))
     }
 
+    /// Adds together levels of a logarithmic unit (i.e. dBm), which can't be summed directly.
+    pub fn sum_levels(
+        &self,
+        unit_type: &str,
+        unit: &str,
+        levels: &[f64],
+    ) -> Result<f64, ConversionError> {
+        let graph_index = self.get_graph_index(unit_type).ok_or(ConversionError::new(
+            "Unable to get internal graph for unit type",
+        ))?;
+        let scale = self.graph[graph_index]
+            .edges()
+            .find_map(|edge| match edge.weight {
+                Conversion::Logarithmic(scale) if !scale.inverse && edge.source == unit => {
+                    Some(*scale)
+                }
+                Conversion::Logarithmic(scale) if scale.inverse && edge.target == unit => {
+                    Some(scale.inverse())
+                }
+                _ => None,
+            })
+            .ok_or(ConversionError::new(&format!(
+                "{} is not a logarithmic unit",
+                unit
+            )))?;
+        scale.sum(levels)
+    }
+
     pub fn units(&self) -> &Vec<UnitAbbreviation> {
         &self.abbreviations
     }
 
+    /// Returns the primary UCUM code of a unit, which is the first code defined for it.
+    pub fn ucum_code(&self, unit_type: &str, unit: &str) -> Option<&str> {
+        self.ucum_codes
+            .iter()
+            .find(|c| c.unit == unit && c.unit_type == unit_type)
+            .map(|c| c.code.as_str())
+    }
+
     fn get_unit_abbrev(&self, unit: &str, unit_type: &str) -> Option<&str> {
         for def in &self.abbreviations {
             if def.unit == unit && def.unit_type == unit_type {
@@ -195,3 +853,74 @@ impl UnitConverter {
         None
     }
 }
+
+/// Converts an integer between numeral systems. The source system is detected from the value
+/// when it isn't given.
+fn convert_numeral(
+    expression: ParsedNumeralExpression,
+    to: NumeralSystem,
+) -> Result<UnitConversion, ConversionError> {
+    let (from, digits) = match expression.from {
+        Some(from) => (
+            NumeralSystem::from_abbrev(from).ok_or(ConversionError::new(&format!(
+                "'{}' is not a valid numeral system",
+                from
+            )))?,
+            expression.value.to_owned(),
+        ),
+        None => NumeralSystem::detect(expression.value),
+    };
+    let value = from.parse(&digits)?;
+    let exact = match to {
+        NumeralSystem::Decimal => ConversionValue::Integer(value),
+        _ => ConversionValue::Text(to.format(value)?),
+    };
+
+    Ok(UnitConversion {
+        value: value as f64,
+        from: from.name().to_owned(),
+        to: to.name().to_owned(),
+        unit_type: NUMERAL_UNIT_TYPE.to_owned(),
+        exact: Some(exact),
+        ..Default::default()
+    })
+}
+
+/// The result of converting along a path in a graph.
+struct PathResult {
+    value: f64,
+    uncertainty: f64,
+    exact: bool,
+    /// The significant figures of the least precise rounded multiplier along the path.
+    significant_figures: Option<u32>,
+    relative_error: f64,
+}
+
+fn min_figures(a: Option<u32>, b: Option<u32>) -> Option<u32> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        _ => a.or(b),
+    }
+}
+
+/// Applies a combined multiplier, which divides it by the value when inverted. Zero has no
+/// reciprocal (i.e. "0 mpg -> L/100km").
+fn apply_multiplier(multiplier: f64, inverted: bool, value: f64) -> Result<f64, ConversionError> {
+    match inverted {
+        true if value == 0.0 => Err(ConversionError::new(
+            "Cannot convert zero through a reciprocal conversion",
+        )),
+        true => Ok(multiplier / value),
+        false => Ok(multiplier * value),
+    }
+}
+
+/// Propagates an uncertainty through `apply_multiplier` using its exact derivative, which avoids
+/// the rounding errors of a numerical one.
+fn multiplier_uncertainty(multiplier: f64, inverted: bool, value: f64, uncertainty: f64) -> f64 {
+    match (inverted, uncertainty == 0.0) {
+        (_, true) => 0.0,
+        (true, false) => (multiplier / (value * value) * uncertainty).abs(),
+        (false, false) => (multiplier * uncertainty).abs(),
+    }
+}
@@ -1,21 +1,41 @@
 use std::collections::HashSet;
 
-use expr::expression::Expression;
-use log::{debug, info};
+use chrono::TimeDelta;
+use expr::expression::{Expression, ExpressionContext, InMemoryExpressionContext};
+use log::{debug, info, warn};
 
 use crate::converter::Conversion;
+use crate::currency::{ExchangeRate, ExchangeRates, CURRENCY_UNIT_TYPE};
 use crate::graph::Graph;
-use crate::parser::UnitAbbreviation;
+use crate::numeral::{NumeralSystem, NUMERAL_UNIT_TYPE};
+use crate::parser::{find_collisions, UnitAbbreviation};
+use crate::precision::Precision;
+use crate::substance::SubstanceRegistry;
+use crate::timezone::TimeZoneAlias;
+use crate::ucum::UcumCode;
 use crate::ConversionDefinition;
 use crate::ConversionValueDefinition;
 
 use super::error::ConversionError;
-use super::UnitConverter;
+use super::{CaseSensitivity, ParseMode, SignificantFigures, UnitConverter, DEFAULT_REGION};
 
 pub struct UnitConverterBuilder {
     unit_types: HashSet<String>,
     conversions: Vec<ConversionDefinition>,
     abbreviations: Vec<UnitAbbreviation>,
+    ucum_codes: Vec<UcumCode>,
+    substances: SubstanceRegistry,
+    exchange_rates: ExchangeRates,
+    max_rate_age: TimeDelta,
+    time_zone_aliases: Vec<TimeZoneAlias>,
+    region: String,
+    case_sensitivity: CaseSensitivity,
+    parse_mode: ParseMode,
+    params: InMemoryExpressionContext,
+    warn_ambiguous_units: bool,
+    exact_arithmetic: bool,
+    significant_figures: SignificantFigures,
+    deny_collisions: bool,
     auto_reverse: bool,
     cache: bool,
 }
@@ -26,6 +46,19 @@ impl Default for UnitConverterBuilder {
             unit_types: HashSet::new(),
             conversions: vec![],
             abbreviations: vec![],
+            ucum_codes: vec![],
+            substances: SubstanceRegistry::default(),
+            exchange_rates: ExchangeRates::default(),
+            max_rate_age: TimeDelta::days(7),
+            time_zone_aliases: vec![],
+            region: String::from(DEFAULT_REGION),
+            case_sensitivity: CaseSensitivity::default(),
+            parse_mode: ParseMode::default(),
+            params: InMemoryExpressionContext::default(),
+            warn_ambiguous_units: false,
+            exact_arithmetic: false,
+            significant_figures: SignificantFigures::default(),
+            deny_collisions: false,
             auto_reverse: false,
             cache: true,
         }
@@ -47,6 +80,63 @@ impl UnitConverterBuilder {
         self
     }
 
+    pub fn parse_mode(mut self, mode: ParseMode) -> UnitConverterBuilder {
+        self.parse_mode = mode;
+        self
+    }
+
+    /// Sets how abbreviations are matched, both in conversions and when looking up a unit.
+    /// Defaults to matching exactly.
+    pub fn case_sensitivity(mut self, case: CaseSensitivity) -> UnitConverterBuilder {
+        self.case_sensitivity = case;
+        self
+    }
+
+    /// Adds a warning to conversions using decimal units that are often confused with binary
+    /// units (i.e. GB when GiB was intended).
+    pub fn warn_ambiguous_units(mut self, warn: bool) -> UnitConverterBuilder {
+        self.warn_ambiguous_units = warn;
+        self
+    }
+
+    /// Combines the multipliers along a conversion path as exact fractions and only converts the
+    /// result to a float at the end, avoiding rounding errors (i.e. 0.0004534065640000001 rather
+    /// than 0.000453406564). Conversions that can't be calculated exactly are marked as
+    /// approximate.
+    pub fn exact_arithmetic(mut self, exact: bool) -> UnitConverterBuilder {
+        self.exact_arithmetic = exact;
+        self
+    }
+
+    /// Rounds results to significant figures, either inferred from the value being converted or
+    /// fixed, and limited by any rounded multipliers used by the conversion. The rounded result is
+    /// given alongside the unrounded value. Defaults to not rounding.
+    pub fn significant_figures(mut self, figures: SignificantFigures) -> UnitConverterBuilder {
+        self.significant_figures = figures;
+        self
+    }
+
+    /// Fails to build the converter if an abbreviation is used by units of several types, rather
+    /// than resolving it using the other unit in each conversion.
+    pub fn deny_abbreviation_collisions(mut self, deny: bool) -> UnitConverterBuilder {
+        self.deny_collisions = deny;
+        self
+    }
+
+    /// Sets the region used to resolve abbreviations shared by regional variants of a unit, i.e.
+    /// "imp" to convert "gal" as Imperial rather than US gallons. Defaults to "us".
+    pub fn default_region(mut self, region: &str) -> UnitConverterBuilder {
+        self.region = region.to_owned();
+        self
+    }
+
+    /// Sets the value of a parameter used by conversion expressions, overriding the default
+    /// declared in the definition.
+    pub fn parameter(mut self, name: &str, value: f64) -> UnitConverterBuilder {
+        self.params.var(name, value);
+        self
+    }
+
     pub fn add_base_conversions(
         mut self,
         mut conversions: Vec<ConversionDefinition>,
@@ -68,12 +158,46 @@ impl UnitConverterBuilder {
         self
     }
 
+    pub fn add_ucum_codes(mut self, mut codes: Vec<UcumCode>) -> UnitConverterBuilder {
+        self.ucum_codes.append(&mut codes);
+        self
+    }
+
+    pub fn add_substances(mut self, substances: SubstanceRegistry) -> UnitConverterBuilder {
+        self.substances.extend(substances);
+        self
+    }
+
+    /// Adds exchange rates for converting between currencies. The currency codes are added as
+    /// units of the Currency type.
+    pub fn add_exchange_rates(mut self, rates: Vec<ExchangeRate>) -> UnitConverterBuilder {
+        self.exchange_rates.extend(rates);
+        self
+    }
+
+    /// Sets how old the exchange rates used for a conversion can be before a warning is raised.
+    /// Defaults to 7 days.
+    pub fn max_rate_age(mut self, age: TimeDelta) -> UnitConverterBuilder {
+        self.max_rate_age = age;
+        self
+    }
+
+    pub fn add_time_zone_aliases(
+        mut self,
+        mut aliases: Vec<TimeZoneAlias>,
+    ) -> UnitConverterBuilder {
+        self.time_zone_aliases.append(&mut aliases);
+        self
+    }
+
     // TODO: Refactor this function to be more readable.
     pub fn build(self) -> Result<UnitConverter, ConversionError> {
         // Populate graph
         let mut graphs = vec![];
+        let mut reversed_edges = vec![];
         for unit_type in &self.unit_types {
             let mut graph = Graph::new(unit_type.to_owned());
+            let mut reversed_indices = vec![];
             let mut count = 0;
 
             for conversion in &self.conversions {
@@ -85,12 +209,17 @@ impl UnitConverterBuilder {
                 let n1 = graph.add_node(conversion.to.clone());
 
                 match &conversion.val {
-                    ConversionValueDefinition::Multiplier(x) => {
+                    ConversionValueDefinition::Multiplier(x)
+                    | ConversionValueDefinition::Approximate(x) => {
                         debug!(
                             "Adding edge to '{}' graph for default conversion {} -> {} (x *= {})",
                             unit_type, &conversion.from, &conversion.to, &conversion.val
                         );
-                        graph.add_edge(n0, n1, Conversion::Multiplier(*x))?;
+                        let precision = match &conversion.val {
+                            ConversionValueDefinition::Approximate(_) => Precision::approximate(*x),
+                            _ => Precision::exact(*x),
+                        };
+                        graph.add_edge(n0, n1, Conversion::Multiplier(*x, precision))?;
 
                         if self.auto_reverse {
                             let reversed = 1.0 / x;
@@ -98,17 +227,81 @@ impl UnitConverterBuilder {
                                 "Adding reversed edge to '{}' graph for {} -> {} (x *= {})",
                                 unit_type, &conversion.to, &conversion.from, reversed
                             );
-                            graph.add_edge(n1, n0, Conversion::Multiplier(reversed))?;
+                            let precision = precision.recip();
+                            reversed_indices.push(graph.edge_count());
+                            graph.add_edge(n1, n0, Conversion::Multiplier(reversed, precision))?;
+                        }
+                    }
+                    ConversionValueDefinition::Reciprocal(x) => {
+                        debug!(
+                            "Adding edge to '{}' graph for default conversion {} -> {} (x = {})",
+                            unit_type, &conversion.from, &conversion.to, &conversion.val
+                        );
+                        let precision = Precision::exact(*x);
+                        graph.add_edge(n0, n1, Conversion::Reciprocal(*x, precision))?;
+
+                        // A reciprocal conversion is its own inverse.
+                        if self.auto_reverse {
+                            debug!(
+                                "Adding reversed edge to '{}' graph for {} -> {} (x = {})",
+                                unit_type, &conversion.to, &conversion.from, &conversion.val
+                            );
+                            reversed_indices.push(graph.edge_count());
+                            graph.add_edge(n1, n0, Conversion::Reciprocal(*x, precision))?;
                         }
                     }
-                    ConversionValueDefinition::Expression(e) => {
+                    ConversionValueDefinition::Expression { expr, params } => {
                         debug!(
                             "Adding edge to '{}' graph for default conversion {} -> {} ({})",
-                            unit_type, &conversion.from, &conversion.to, e
+                            unit_type, &conversion.from, &conversion.to, &conversion.val
                         );
-                        let expr = Expression::new(e)?;
+                        let mut expr = Expression::new(expr)?;
+                        for (name, default) in params {
+                            expr.ctx.var(name, *default);
+                        }
                         graph.add_edge(n0, n1, Conversion::Expression(expr))?;
                     }
+                    ConversionValueDefinition::Table(table) => {
+                        debug!(
+                            "Adding edge to '{}' graph for default conversion {} -> {} ({})",
+                            unit_type, &conversion.from, &conversion.to, &conversion.val
+                        );
+                        graph.add_edge(n0, n1, Conversion::Table(table.clone()))?;
+
+                        if self.auto_reverse {
+                            match table.inverse() {
+                                Some(inverse) => {
+                                    debug!(
+                                        "Adding reversed edge to '{}' graph for {} -> {} ({})",
+                                        unit_type, &conversion.to, &conversion.from, &conversion.val
+                                    );
+                                    reversed_indices.push(graph.edge_count());
+                                    graph.add_edge(n1, n0, Conversion::Table(inverse))?;
+                                }
+                                None => warn!(
+                                    "Unable to reverse lookup table for {} -> {} as its values are not strictly increasing or decreasing",
+                                    &conversion.from, &conversion.to
+                                ),
+                            }
+                        }
+                    }
+                    ConversionValueDefinition::Logarithmic(scale) => {
+                        debug!(
+                            "Adding edge to '{}' graph for default conversion {} -> {} (x = {})",
+                            unit_type, &conversion.from, &conversion.to, &conversion.val
+                        );
+                        graph.add_edge(n0, n1, Conversion::Logarithmic(*scale))?;
+
+                        if self.auto_reverse {
+                            let reversed = scale.inverse();
+                            debug!(
+                                "Adding reversed edge to '{}' graph for {} -> {} (x = {})",
+                                unit_type, &conversion.to, &conversion.from, reversed
+                            );
+                            reversed_indices.push(graph.edge_count());
+                            graph.add_edge(n1, n0, Conversion::Logarithmic(reversed))?;
+                        }
+                    }
                 }
 
                 count += 1;
@@ -118,12 +311,64 @@ impl UnitConverterBuilder {
                 &unit_type, count
             );
             graphs.push(graph);
+            reversed_edges.push(reversed_indices);
+        }
+
+        let mut abbreviations = self.abbreviations;
+        let definition_abbreviations = abbreviations.len();
+        for currency in self.exchange_rates.currencies() {
+            if !abbreviations.iter().any(|a| a.abbrev == currency) {
+                abbreviations.push(UnitAbbreviation {
+                    unit: currency.to_owned(),
+                    abbrev: currency.to_owned(),
+                    unit_type: CURRENCY_UNIT_TYPE.to_owned(),
+                    region: None,
+                });
+            }
+        }
+        for system in NumeralSystem::ALL {
+            if !abbreviations.iter().any(|a| a.abbrev == system.abbrev()) {
+                abbreviations.push(UnitAbbreviation {
+                    unit: system.name().to_owned(),
+                    abbrev: system.abbrev().to_owned(),
+                    unit_type: NUMERAL_UNIT_TYPE.to_owned(),
+                    region: None,
+                });
+            }
+        }
+
+        let collisions = find_collisions(&abbreviations);
+        for collision in &collisions {
+            warn!("{}", collision);
+        }
+        if self.deny_collisions && !collisions.is_empty() {
+            let collisions: Vec<String> = collisions.iter().map(|c| c.to_string()).collect();
+            return Err(ConversionError::new(&format!(
+                "Abbreviations are used by several unit types: {}",
+                collisions.join("; ")
+            )));
         }
 
         info!(
             "Finished building unit converter object. Contains graphs for {} unit type(s) and definitions for {} unit(s)",
-            graphs.len(), &self.abbreviations.len()
+            graphs.len(), &abbreviations.len()
         );
-        Ok(UnitConverter::new(graphs, self.abbreviations, self.cache))
+        let mut converter = UnitConverter::new(graphs, abbreviations, self.cache);
+        converter.reversed_edges = reversed_edges;
+        converter.definition_abbreviations = definition_abbreviations;
+        converter.ucum_codes = self.ucum_codes;
+        converter.parse_mode = self.parse_mode;
+        converter.params = self.params;
+        converter.warn_ambiguous_units = self.warn_ambiguous_units;
+        converter.exact_arithmetic = self.exact_arithmetic;
+        converter.significant_figures = self.significant_figures;
+        converter.substances = self.substances;
+        converter.exchange_rates = self.exchange_rates;
+        converter.max_rate_age = self.max_rate_age;
+        converter.time_zone_aliases = self.time_zone_aliases;
+        converter.region = self.region;
+        converter.case_sensitivity = self.case_sensitivity;
+        converter.collisions = collisions;
+        Ok(converter)
     }
 }
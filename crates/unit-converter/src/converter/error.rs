@@ -52,6 +52,19 @@ impl From<toml::de::Error> for ConversionError {
     }
 }
 
+impl From<toml::ser::Error> for ConversionError {
+    fn from(value: toml::ser::Error) -> Self {
+        ConversionError::new(&value.to_string())
+    }
+}
+
+#[cfg(feature = "json")]
+impl From<serde_json::Error> for ConversionError {
+    fn from(value: serde_json::Error) -> Self {
+        ConversionError::new(&value.to_string())
+    }
+}
+
 // TODO: Fix up this from trait so that it contains a proper error message
 impl From<ExpressionError> for ConversionError {
     fn from(_value: ExpressionError) -> Self {
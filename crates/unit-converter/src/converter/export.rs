@@ -0,0 +1,358 @@
+use toml::{Table, Value};
+
+use crate::parser::UnitAbbreviation;
+use crate::precision::Precision;
+
+use super::error::ConversionError;
+use super::{Conversion, UnitConverter};
+
+impl UnitConverter {
+    /// Serializes the unit abbreviations to the format used by `UnitDefinitionSourceToml`.
+    /// Abbreviations generated when loading or building (i.e. "gal_us" and currency codes) are
+    /// not included.
+    pub fn export_units_toml(&self) -> Result<String, ConversionError> {
+        Ok(toml::to_string(&self.units_table())?)
+    }
+
+    /// Serializes the conversions in every graph to the format used by
+    /// `BaseConversionsSourceToml`. Conversions which have been cached are not included, nor are
+    /// those added by reversing a definition, which are added again when the definitions are
+    /// loaded with `reverse_base_conversions` enabled.
+    pub fn export_conversions_toml(&self) -> Result<String, ConversionError> {
+        Ok(toml::to_string(&self.conversions_table())?)
+    }
+
+    #[cfg(feature = "json")]
+    pub fn export_units_json(&self) -> Result<String, ConversionError> {
+        Ok(serde_json::to_string_pretty(&self.units_table())?)
+    }
+
+    #[cfg(feature = "json")]
+    pub fn export_conversions_json(&self) -> Result<String, ConversionError> {
+        Ok(serde_json::to_string_pretty(&self.conversions_table())?)
+    }
+
+    fn units_table(&self) -> Table {
+        let mut result = Table::new();
+        let definitions = &self.abbreviations[..self.definition_abbreviations];
+        for abbrev in definitions {
+            if is_qualified_with_region(definitions, abbrev) {
+                continue;
+            }
+            let category = table_entry(&mut result, &abbrev.unit_type);
+            let entry = category
+                .entry(&abbrev.unit)
+                .or_insert_with(|| match &abbrev.region {
+                    Some(region) => {
+                        let mut variant = Table::new();
+                        variant.insert(String::from("region"), Value::String(region.to_owned()));
+                        variant.insert(String::from("abbrevs"), Value::Array(vec![]));
+                        Value::Table(variant)
+                    }
+                    None => Value::Array(vec![]),
+                });
+            let abbreviations = match entry {
+                Value::Table(variant) => variant.get_mut("abbrevs"),
+                abbreviations => Some(abbreviations),
+            };
+            if let Some(Value::Array(abbreviations)) = abbreviations {
+                abbreviations.push(Value::String(abbrev.abbrev.to_owned()));
+            }
+        }
+        result
+    }
+
+    fn conversions_table(&self) -> Table {
+        let mut result = Table::new();
+        let graphs = self.graph.iter().zip(&self.definition_edges).enumerate();
+        for (i, (graph, definition_edges)) in graphs {
+            let reversed = self.reversed_edges.get(i);
+            for (index, edge) in graph.edges().take(*definition_edges).enumerate() {
+                if reversed.is_some_and(|reversed| reversed.contains(&index)) {
+                    continue;
+                }
+                let category = table_entry(&mut result, &graph.id);
+                let conversions = table_entry(category, edge.source);
+                conversions.insert(edge.target.to_owned(), conversion_value(edge.weight));
+            }
+        }
+        result
+    }
+}
+
+/// Whether an abbreviation is a regional variant's abbreviation qualified with its region (i.e.
+/// "gal_us" for "gal"), which is added by `UnitDefinitionSourceToml`.
+fn is_qualified_with_region(units: &[UnitAbbreviation], unit: &UnitAbbreviation) -> bool {
+    let Some(region) = &unit.region else {
+        return false;
+    };
+    unit.abbrev
+        .strip_suffix(region.as_str())
+        .and_then(|abbrev| abbrev.strip_suffix('_'))
+        .is_some_and(|abbrev| {
+            units
+                .iter()
+                .any(|u| u.abbrev == abbrev && u.unit == unit.unit && u.unit_type == unit.unit_type)
+        })
+}
+
+fn table_entry<'a>(table: &'a mut Table, key: &str) -> &'a mut Table {
+    let value = table
+        .entry(key)
+        .or_insert_with(|| Value::Table(Table::new()));
+    match value {
+        Value::Table(tbl) => tbl,
+        _ => unreachable!(),
+    }
+}
+
+fn conversion_value(conversion: &Conversion) -> Value {
+    match conversion {
+        Conversion::Multiplier(val, Precision::Approximate(_) | Precision::Combined(..)) => {
+            let mut approximate = Table::new();
+            approximate.insert(String::from("approx"), Value::Float(*val));
+            Value::Table(approximate)
+        }
+        Conversion::Multiplier(val, _) => Value::Float(*val),
+        Conversion::Reciprocal(val, _) => {
+            let mut reciprocal = Table::new();
+            reciprocal.insert(String::from("reciprocal"), Value::Float(*val));
+            Value::Table(reciprocal)
+        }
+        Conversion::Expression(expr) if expr.ctx.vars.is_empty() => {
+            Value::String(expr.expr.to_owned())
+        }
+        Conversion::Expression(expr) => {
+            let params = expr
+                .ctx
+                .vars
+                .iter()
+                .map(|(name, default)| (name.to_owned(), Value::Float(*default)))
+                .collect();
+            let mut expression = Table::new();
+            expression.insert(String::from("expr"), Value::String(expr.expr.to_owned()));
+            expression.insert(String::from("params"), Value::Table(params));
+            Value::Table(expression)
+        }
+        Conversion::Table(table) => {
+            let points = table
+                .points
+                .iter()
+                .map(|(x, y)| Value::Array(vec![Value::Float(*x), Value::Float(*y)]))
+                .collect();
+            let mut lookup = Table::new();
+            lookup.insert(String::from("table"), Value::Array(points));
+            lookup.insert(
+                String::from("interpolation"),
+                Value::String(table.interpolation.to_string()),
+            );
+            Value::Table(lookup)
+        }
+        Conversion::Logarithmic(scale) => {
+            let mut log = Table::new();
+            log.insert(String::from("reference"), Value::Float(scale.reference));
+            log.insert(String::from("factor"), Value::Float(scale.factor));
+            log.insert(String::from("base"), Value::Float(scale.base));
+            if scale.inverse {
+                log.insert(String::from("inverse"), Value::Boolean(true));
+            }
+            Value::Table(log)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source::toml::conversions::BaseConversionsSourceToml;
+    use crate::source::toml::units::UnitDefinitionSourceToml;
+    use crate::table::{Interpolation, LookupTable};
+    use crate::{ConversionDefinition, ConversionValueDefinition};
+
+    fn construct_converter() -> UnitConverter {
+        let units = [
+            ("Kilometers", "km", "Length"),
+            ("Meters", "m", "Length"),
+            ("Millimeters", "mm", "Length"),
+            ("AmericanWireGauge", "AWG", "Length"),
+            ("Celsius", "C", "Temperature"),
+            ("Kelvin", "K", "Temperature"),
+        ];
+        let conversions = [
+            ("Length", "Kilometers", "Meters", "1000"),
+            ("Length", "Meters", "Millimeters", "1000"),
+            ("Length", "AmericanWireGauge", "Millimeters", "table"),
+            ("Temperature", "Celsius", "Kelvin", "C + 273.15"),
+        ];
+
+        UnitConverter::builder()
+            .reverse_base_conversions(true)
+            .add_unit_definitions(
+                units
+                    .iter()
+                    .map(|(unit, abbrev, unit_type)| UnitAbbreviation {
+                        unit: unit.to_string(),
+                        abbrev: abbrev.to_string(),
+                        unit_type: unit_type.to_string(),
+                        region: None,
+                    })
+                    .collect(),
+            )
+            .add_base_conversions(
+                conversions
+                    .iter()
+                    .map(|(category, from, to, val)| ConversionDefinition {
+                        category: category.to_string(),
+                        from: from.to_string(),
+                        to: to.to_string(),
+                        val: match val.parse::<f64>() {
+                            Err(_) if *val == "table" => ConversionValueDefinition::Table(
+                                LookupTable::new(
+                                    vec![(0.0, 8.2515), (1.0, 7.3481), (2.0, 6.5437)],
+                                    Interpolation::Linear,
+                                )
+                                .unwrap(),
+                            ),
+                            Ok(x) => ConversionValueDefinition::Multiplier(x),
+                            Err(_) => ConversionValueDefinition::Expression {
+                                expr: val.to_string(),
+                                params: vec![],
+                            },
+                        },
+                    })
+                    .collect(),
+            )
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn export_units() {
+        let converter = construct_converter();
+        let actual = converter
+            .export_units_toml()
+            .unwrap()
+            .parse::<Table>()
+            .unwrap();
+
+        assert_eq!(
+            Some(&Value::Array(vec![Value::String(String::from("km"))])),
+            actual["Length"].get("Kilometers")
+        );
+        assert_eq!(4, actual["Length"].as_table().unwrap().len());
+        assert_eq!(None, actual.get("Numeral"));
+    }
+
+    #[test]
+    fn export_regional_units_round_trip() {
+        let contents = "[Capacity]\n\
+            Liters = ['l']\n\
+            USGallons = { region = 'us', abbrevs = ['gal'] }\n\
+            ImperialGallons = { region = 'imp', abbrevs = ['gal'] }\n";
+        let units = UnitDefinitionSourceToml::from_contents("units", contents)
+            .load()
+            .unwrap();
+        let converter = UnitConverter::builder()
+            .add_unit_definitions(units.clone())
+            .build()
+            .unwrap();
+        let exported = converter.export_units_toml().unwrap();
+
+        let actual = exported.parse::<Table>().unwrap();
+        assert_eq!(
+            Some(&Value::String(String::from("us"))),
+            actual["Capacity"]["USGallons"].get("region")
+        );
+        assert_eq!(
+            Some(&Value::Array(vec![Value::String(String::from("gal"))])),
+            actual["Capacity"]["USGallons"].get("abbrevs")
+        );
+
+        let reloaded = UnitDefinitionSourceToml::from_contents("exported", &exported)
+            .load()
+            .unwrap();
+        assert_eq!(units.len(), reloaded.len());
+        for unit in &units {
+            assert!(
+                reloaded.iter().any(|u| u.abbrev == unit.abbrev
+                    && u.unit == unit.unit
+                    && u.region == unit.region),
+                "{:?}",
+                unit
+            );
+        }
+    }
+
+    #[test]
+    fn export_conversions_excludes_cached_and_reversed_edges() {
+        let mut converter = construct_converter();
+        _ = converter
+            .convert_from_definition("Length", "Kilometers", "Millimeters", 1.0)
+            .unwrap();
+
+        let actual = converter
+            .export_conversions_toml()
+            .unwrap()
+            .parse::<Table>()
+            .unwrap();
+
+        let kilometers = actual["Length"]["Kilometers"].as_table().unwrap();
+        assert_eq!(Some(&Value::Float(1000.0)), kilometers.get("Meters"));
+        assert_eq!(None, kilometers.get("Millimeters"));
+        assert_eq!(None, actual["Length"]["Meters"].get("Kilometers"));
+        assert_eq!(None, actual["Length"].get("Millimeters"));
+        assert_eq!(
+            Some(&Value::String(String::from("C + 273.15"))),
+            actual["Temperature"]["Celsius"].get("Kelvin")
+        );
+    }
+
+    #[test]
+    fn export_round_trip() {
+        let mut converter = construct_converter();
+        let units = UnitDefinitionSourceToml::from_contents(
+            "units",
+            &converter.export_units_toml().unwrap(),
+        )
+        .load()
+        .unwrap();
+        let conversions = BaseConversionsSourceToml::from_contents(
+            "conversions",
+            &converter.export_conversions_toml().unwrap(),
+        )
+        .load()
+        .unwrap();
+        let mut reloaded = UnitConverter::builder()
+            .reverse_base_conversions(true)
+            .add_unit_definitions(units)
+            .add_base_conversions(conversions)
+            .build()
+            .unwrap();
+
+        for input in [
+            "3km -> mm",
+            "5mm -> km",
+            "20C -> K",
+            "1.5AWG -> m",
+            "7mm -> AWG",
+        ] {
+            let expected = converter.convert_from_expression(input).unwrap();
+            let actual = reloaded.convert_from_expression(input).unwrap();
+            assert_eq!(expected, actual);
+        }
+        assert_eq!(
+            converter.export_conversions_toml().unwrap(),
+            reloaded.export_conversions_toml().unwrap()
+        );
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn export_json() {
+        let converter = construct_converter();
+        let actual = converter.export_conversions_json().unwrap();
+        let actual: serde_json::Value = serde_json::from_str(&actual).unwrap();
+
+        assert_eq!(1000.0, actual["Length"]["Kilometers"]["Meters"]);
+    }
+}
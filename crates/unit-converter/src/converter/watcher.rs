@@ -0,0 +1,297 @@
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, SystemTime};
+
+use log::{debug, error, info};
+
+use super::error::ConversionError;
+use super::UnitConverter;
+
+type BuildFn = Box<dyn Fn() -> Result<UnitConverter, ConversionError> + Send>;
+type ReloadFn = Box<dyn Fn(&ReloadOutcome) + Send>;
+
+pub enum ReloadOutcome {
+    Reloaded,
+    Failed(ConversionError),
+}
+
+/// Keeps a shared `UnitConverter` up to date with its definition files. The files are polled for
+/// changes, and when one changes the converter is rebuilt and swapped in. If the rebuild fails
+/// the previous converter stays active.
+pub struct ConverterWatcher {
+    converter: Arc<Mutex<UnitConverter>>,
+    running: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+pub struct ConverterWatcherBuilder {
+    build: BuildFn,
+    paths: Vec<PathBuf>,
+    interval: Duration,
+    on_reload: Option<ReloadFn>,
+}
+
+impl ConverterWatcher {
+    /// Creates a watcher which uses `build` to create the converter, both initially and whenever
+    /// a watched file changes.
+    pub fn builder(
+        build: impl Fn() -> Result<UnitConverter, ConversionError> + Send + 'static,
+    ) -> ConverterWatcherBuilder {
+        ConverterWatcherBuilder {
+            build: Box::new(build),
+            paths: vec![],
+            interval: Duration::from_secs(2),
+            on_reload: None,
+        }
+    }
+
+    pub fn converter(&self) -> Arc<Mutex<UnitConverter>> {
+        Arc::clone(&self.converter)
+    }
+
+    pub fn stop(&mut self) {
+        self.running.store(false, Ordering::SeqCst);
+        if let Some(handle) = self.handle.take() {
+            handle.thread().unpark();
+            _ = handle.join();
+        }
+    }
+}
+
+impl Drop for ConverterWatcher {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+impl ConverterWatcherBuilder {
+    pub fn watch(mut self, path: &str) -> ConverterWatcherBuilder {
+        self.paths.push(PathBuf::from(path));
+        self
+    }
+
+    pub fn poll_interval(mut self, interval: Duration) -> ConverterWatcherBuilder {
+        self.interval = interval;
+        self
+    }
+
+    pub fn on_reload(
+        mut self,
+        on_reload: impl Fn(&ReloadOutcome) + Send + 'static,
+    ) -> ConverterWatcherBuilder {
+        self.on_reload = Some(Box::new(on_reload));
+        self
+    }
+
+    /// Builds the initial converter and starts watching for changes. Fails if the initial
+    /// converter can't be built.
+    pub fn start(self) -> Result<ConverterWatcher, ConversionError> {
+        let mut snapshot = file_snapshot(&self.paths);
+        let converter = Arc::new(Mutex::new((self.build)()?));
+        let running = Arc::new(AtomicBool::new(true));
+
+        let shared = Arc::clone(&converter);
+        let is_running = Arc::clone(&running);
+        let handle = thread::spawn(move || {
+            info!(
+                "Watching {} definition file(s) for changes",
+                self.paths.len()
+            );
+            while is_running.load(Ordering::SeqCst) {
+                thread::park_timeout(self.interval);
+                if !is_running.load(Ordering::SeqCst) {
+                    break;
+                }
+
+                let current = file_snapshot(&self.paths);
+                if current == snapshot {
+                    continue;
+                }
+                snapshot = current;
+
+                debug!("Definition files changed, rebuilding unit converter");
+                let outcome = match (self.build)() {
+                    Ok(converter) => {
+                        // The rebuilt converter replaces the old one entirely, so a lock poisoned
+                        // by a panic elsewhere is still safe to use.
+                        *shared.lock().unwrap_or_else(PoisonError::into_inner) = converter;
+                        info!("Reloaded unit converter");
+                        ReloadOutcome::Reloaded
+                    }
+                    Err(err) => {
+                        error!("Unable to reload unit converter, keeping previous: {}", err);
+                        ReloadOutcome::Failed(err)
+                    }
+                };
+
+                if let Some(on_reload) = &self.on_reload {
+                    on_reload(&outcome);
+                }
+            }
+        });
+
+        Ok(ConverterWatcher {
+            converter,
+            running,
+            handle: Some(handle),
+        })
+    }
+}
+
+/// The modification time, length and a hash of the contents of each file. The contents are
+/// hashed because an edit that keeps the length can be made within the granularity of the
+/// modification time, which is a second or more on some filesystems.
+fn file_snapshot(paths: &[PathBuf]) -> Vec<Option<(SystemTime, u64, u64)>> {
+    paths
+        .iter()
+        .map(|path| {
+            let metadata = std::fs::metadata(path).ok()?;
+            let mut hasher = DefaultHasher::new();
+            std::fs::read(path).ok()?.hash(&mut hasher);
+            Some((metadata.modified().ok()?, metadata.len(), hasher.finish()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+
+    use super::*;
+    use crate::source::toml::conversions::BaseConversionsSourceToml;
+    use crate::source::toml::units::UnitDefinitionSourceToml;
+
+    const UNITS: &str = "[Length]\nKilometers = ['km']\nMeters = ['m']\n";
+    const CONVERSIONS: &str = "[Length]\nKilometers = { Meters = 1000 }\n";
+
+    /// A directory for a test's definition files, which is removed when dropped.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> TempDir {
+            let dir = std::env::temp_dir().join(format!(
+                "unitconvert_watcher_{}_{}",
+                std::process::id(),
+                name
+            ));
+            std::fs::create_dir_all(&dir).unwrap();
+            TempDir(dir)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn start_watcher(name: &str) -> (TempDir, ConverterWatcher, mpsc::Receiver<bool>) {
+        let dir = TempDir::new(name);
+        let units_path = dir.0.join("Units.toml");
+        let conversions_path = dir.0.join("Base_Conversions.toml");
+        std::fs::write(&units_path, UNITS).unwrap();
+        std::fs::write(&conversions_path, CONVERSIONS).unwrap();
+
+        let units = units_path.to_str().unwrap().to_owned();
+        let conversions = conversions_path.to_str().unwrap().to_owned();
+        let (sender, receiver) = mpsc::channel();
+        let watcher = ConverterWatcher::builder(move || {
+            UnitConverter::builder()
+                .add_unit_definitions(UnitDefinitionSourceToml::new(&units).load()?)
+                .add_base_conversions(BaseConversionsSourceToml::new(&conversions).load()?)
+                .build()
+        })
+        .watch(units_path.to_str().unwrap())
+        .watch(conversions_path.to_str().unwrap())
+        .poll_interval(Duration::from_millis(10))
+        .on_reload(move |outcome| {
+            _ = sender.send(matches!(outcome, ReloadOutcome::Reloaded));
+        })
+        .start()
+        .unwrap();
+
+        (dir, watcher, receiver)
+    }
+
+    #[test]
+    fn reload_on_change() {
+        let (dir, watcher, receiver) = start_watcher("reload_on_change");
+        let converter = watcher.converter();
+        assert!(converter
+            .lock()
+            .unwrap()
+            .convert_from_expression("1km -> cm")
+            .is_err());
+
+        std::fs::write(
+            dir.0.join("Units.toml"),
+            format!("{}Centimeters = ['cm']\n", UNITS),
+        )
+        .unwrap();
+        std::fs::write(
+            dir.0.join("Base_Conversions.toml"),
+            format!("{}Meters = {{ Centimeters = 100 }}\n", CONVERSIONS),
+        )
+        .unwrap();
+
+        // Both files may not have been written when the first change was detected, so wait for
+        // reloads until one picks up both.
+        let actual = loop {
+            assert!(receiver.recv_timeout(Duration::from_secs(30)).unwrap());
+            if let Ok(actual) = converter
+                .lock()
+                .unwrap()
+                .convert_from_expression("1km -> cm")
+            {
+                break actual;
+            }
+        };
+        assert_eq!(100000.0, actual.value);
+    }
+
+    #[test]
+    fn keep_previous_converter_on_failure() {
+        let (dir, watcher, receiver) = start_watcher("keep_previous_converter_on_failure");
+
+        std::fs::write(dir.0.join("Units.toml"), "[Length]\nMeters = 'm'\n").unwrap();
+
+        assert!(!receiver.recv_timeout(Duration::from_secs(30)).unwrap());
+        let actual = watcher
+            .converter()
+            .lock()
+            .unwrap()
+            .convert_from_expression("1km -> m")
+            .unwrap();
+        assert_eq!(1000.0, actual.value);
+    }
+
+    #[test]
+    fn reload_after_poisoned_lock() {
+        let (dir, watcher, receiver) = start_watcher("reload_after_poisoned_lock");
+        let converter = watcher.converter();
+        let poisoned = Arc::clone(&converter);
+        _ = thread::spawn(move || {
+            let _guard = poisoned.lock().unwrap();
+            panic!("poison the converter lock");
+        })
+        .join();
+        assert!(converter.is_poisoned());
+
+        std::fs::write(
+            dir.0.join("Base_Conversions.toml"),
+            "[Length]\nKilometers = { Meters = 1000.5 }\n",
+        )
+        .unwrap();
+
+        assert!(receiver.recv_timeout(Duration::from_secs(30)).unwrap());
+        let actual = converter
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .convert_from_expression("1km -> m")
+            .unwrap();
+        assert_eq!(1000.5, actual.value);
+    }
+}
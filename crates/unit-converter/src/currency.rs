@@ -0,0 +1,167 @@
+use chrono::NaiveDate;
+
+use crate::converter::error::ConversionError;
+
+pub const CURRENCY_UNIT_TYPE: &str = "Currency";
+
+/// The exchange rate between two currencies from a date, where one unit of the base currency is
+/// equal to `rate` units of the currency.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ExchangeRate {
+    pub date: NaiveDate,
+    pub base: String,
+    pub currency: String,
+    pub rate: f64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ExchangeRates {
+    rates: Vec<ExchangeRate>,
+}
+
+impl ExchangeRates {
+    pub fn extend(&mut self, mut rates: Vec<ExchangeRate>) {
+        self.rates.append(&mut rates);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rates.is_empty()
+    }
+
+    /// Returns every currency with a rate, including the base currencies.
+    pub fn currencies(&self) -> Vec<&str> {
+        let mut result: Vec<&str> = vec![];
+        for rate in &self.rates {
+            for currency in [&rate.base, &rate.currency] {
+                if !result.contains(&currency.as_str()) {
+                    result.push(currency);
+                }
+            }
+        }
+        result
+    }
+
+    /// Converts between currencies using the latest rates effective on `date`, or the latest
+    /// rates overall if no date is given. Returns the converted value along with the date of the
+    /// oldest rate used.
+    pub fn convert(
+        &self,
+        from: &str,
+        to: &str,
+        value: f64,
+        date: Option<NaiveDate>,
+    ) -> Result<(f64, NaiveDate), ConversionError> {
+        let mut bases: Vec<&str> = vec![];
+        for rate in &self.rates {
+            if !bases.contains(&rate.base.as_str()) {
+                bases.push(&rate.base);
+            }
+        }
+
+        for base in bases {
+            let (Some(from_rate), Some(to_rate)) =
+                (self.rate(base, from, date), self.rate(base, to, date))
+            else {
+                continue;
+            };
+            let (from_rate, from_date) = from_rate;
+            let (to_rate, to_date) = to_rate;
+            return Ok((value / from_rate * to_rate, from_date.min(to_date)));
+        }
+
+        Err(ConversionError::new(&match date {
+            Some(date) => format!(
+                "No exchange rate between {} and {} on or before {}",
+                from, to, date
+            ),
+            None => format!("No exchange rate between {} and {}", from, to),
+        }))
+    }
+
+    /// Finds the rate of a currency against a base currency, with the date it's effective from.
+    /// The base currency itself has a rate of 1 from the date of its latest rate.
+    fn rate(
+        &self,
+        base: &str,
+        currency: &str,
+        date: Option<NaiveDate>,
+    ) -> Option<(f64, NaiveDate)> {
+        self.rates
+            .iter()
+            .filter(|r| r.base == base && (currency == base || r.currency == currency))
+            .filter(|r| date.is_none_or(|date| r.date <= date))
+            .max_by_key(|r| r.date)
+            .map(|r| match currency == base {
+                true => (1.0, r.date),
+                false => (r.rate, r.date),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn construct_rates() -> ExchangeRates {
+        let rates = [
+            ("2024-03-01", "EUR", "USD", 1.08),
+            ("2024-03-01", "EUR", "GBP", 0.85),
+            ("2024-06-03", "EUR", "USD", 1.09),
+        ];
+        let mut result = ExchangeRates::default();
+        result.extend(
+            rates
+                .iter()
+                .map(|(date, base, currency, rate)| ExchangeRate {
+                    date: date.parse().unwrap(),
+                    base: base.to_string(),
+                    currency: currency.to_string(),
+                    rate: *rate,
+                })
+                .collect(),
+        );
+        result
+    }
+
+    #[test]
+    fn latest_rate() {
+        let rates = construct_rates();
+        let (value, date) = rates.convert("EUR", "USD", 100.0, None).unwrap();
+        assert_eq!(109.00000000000001, value);
+        assert_eq!(NaiveDate::from_ymd_opt(2024, 6, 3).unwrap(), date);
+    }
+
+    #[test]
+    fn historical_rate() {
+        let rates = construct_rates();
+        let date = NaiveDate::from_ymd_opt(2024, 5, 1);
+        let (value, actual_date) = rates.convert("USD", "EUR", 108.0, date).unwrap();
+        assert_eq!(100.0, value);
+        assert_eq!(NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(), actual_date);
+    }
+
+    #[test]
+    fn cross_rate_uses_oldest_date() {
+        let rates = construct_rates();
+        let (value, date) = rates.convert("GBP", "USD", 85.0, None).unwrap();
+        assert_eq!(109.00000000000001, value);
+        assert_eq!(NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(), date);
+    }
+
+    #[test]
+    fn no_rate_before_date() {
+        let rates = construct_rates();
+        let date = NaiveDate::from_ymd_opt(2023, 12, 31);
+        let actual = rates.convert("EUR", "USD", 100.0, date);
+        assert_eq!(
+            "No exchange rate between EUR and USD on or before 2023-12-31",
+            actual.unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn currencies() {
+        let rates = construct_rates();
+        assert_eq!(vec!["EUR", "USD", "GBP"], rates.currencies());
+    }
+}
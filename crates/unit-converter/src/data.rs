@@ -0,0 +1,60 @@
+/// Decimal units that are commonly used to mean the binary unit of the same magnitude (i.e. "GB"
+/// when GiB was intended), along with that binary unit.
+const AMBIGUOUS_UNITS: [(&str, &str); 8] = [
+    ("Kilobytes", "Kibibytes (KiB)"),
+    ("Megabytes", "Mebibytes (MiB)"),
+    ("Gigabytes", "Gibibytes (GiB)"),
+    ("Terabytes", "Tebibytes (TiB)"),
+    ("Petabytes", "Pebibytes (PiB)"),
+    ("KilobytesPerSecond", "KibibytesPerSecond (KiB/s)"),
+    ("MegabytesPerSecond", "MebibytesPerSecond (MiB/s)"),
+    ("GigabytesPerSecond", "GibibytesPerSecond (GiB/s)"),
+];
+
+/// Returns a warning if the unit is a decimal unit that is often confused with a binary unit.
+pub fn ambiguous_unit_warning(unit: &str) -> Option<String> {
+    AMBIGUOUS_UNITS
+        .iter()
+        .find(|(decimal, _)| *decimal == unit)
+        .map(|(decimal, binary)| {
+            format!(
+                "{} are decimal units of 1000, use {} if binary units of 1024 were intended",
+                decimal, binary
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source::toml::units::UnitDefinitionSourceToml;
+
+    #[test]
+    fn ambiguous_unit() {
+        assert_eq!(
+            Some(String::from(
+                "Gigabytes are decimal units of 1000, use Gibibytes (GiB) if binary units of 1024 were intended"
+            )),
+            ambiguous_unit_warning("Gigabytes")
+        );
+        assert_eq!(None, ambiguous_unit_warning("Gibibytes"));
+        assert_eq!(None, ambiguous_unit_warning("Megabits"));
+    }
+
+    #[test]
+    fn ambiguous_units_are_defined() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../../Units.toml");
+        let abbreviations = UnitDefinitionSourceToml::new(path).load().unwrap();
+        let is_defined = |unit: &str, abbrev: Option<&str>| {
+            abbreviations
+                .iter()
+                .any(|a| a.unit == unit && abbrev.is_none_or(|abbrev| a.abbrev == abbrev))
+        };
+
+        for (decimal, binary) in AMBIGUOUS_UNITS {
+            assert!(is_defined(decimal, None), "{}", decimal);
+            let (unit, abbrev) = binary.trim_end_matches(')').split_once(" (").unwrap();
+            assert!(is_defined(unit, Some(abbrev)), "{}", binary);
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use nom::{
+    bytes::complete::tag,
+    character::complete::one_of,
+    combinator::opt,
+    multi::many0,
+    sequence::{pair, preceded},
+    IResult,
+};
+
+use crate::parser::parse_number;
+
+/// The abbreviation of the unit that parsed ISO 8601 durations are given in.
+pub const DURATION_UNIT: &str = "s";
+
+const MINUTE: f64 = 60.0;
+const HOUR: f64 = 60.0 * MINUTE;
+const DAY: f64 = 24.0 * HOUR;
+const WEEK: f64 = 7.0 * DAY;
+/// The average length of a year in the Gregorian calendar, which is used for the years and
+/// months of a duration as they have no fixed length.
+const YEAR: f64 = 365.2425 * DAY;
+const MONTH: f64 = YEAR / 12.0;
+
+/// Parses an ISO 8601 duration (i.e. "P1DT2H30M" or "PT0.5S") into seconds.
+pub fn parse_iso_duration(input: &str) -> IResult<&str, f64> {
+    let (rest, _) = tag("P")(input)?;
+    let (rest, date) = many0(pair(parse_number, one_of("YMWD")))(rest)?;
+    let (rest, time) = opt(preceded(tag("T"), many0(pair(parse_number, one_of("HMS")))))(rest)?;
+    let time = time.unwrap_or_default();
+    if date.is_empty() && time.is_empty() {
+        return Err(nom::Err::Error(nom::error::Error::new(
+            input,
+            nom::error::ErrorKind::Float,
+        )));
+    }
+
+    let date = date.iter().map(|(value, designator)| {
+        value
+            * match designator {
+                'Y' => YEAR,
+                'M' => MONTH,
+                'W' => WEEK,
+                _ => DAY,
+            }
+    });
+    let time = time.iter().map(|(value, designator)| {
+        value
+            * match designator {
+                'H' => HOUR,
+                'M' => MINUTE,
+                _ => 1.0,
+            }
+    });
+    Ok((rest, date.chain(time).sum()))
+}
+
+/// Splits a value into whole amounts of each unit, with any remainder given to the last unit.
+/// The factors are the size of each unit in terms of the last unit, and the value is given in
+/// terms of the last unit.
+pub fn split_components(value: f64, factors: &[f64]) -> Vec<f64> {
+    let mut remaining = value.abs();
+    let mut result = vec![];
+    for (i, factor) in factors.iter().enumerate() {
+        let amount = match i == factors.len() - 1 {
+            true => remaining / factor,
+            // Allow for a small amount of floating point error (i.e. 2.9999999999 hours).
+            false => (remaining / factor + 1e-9).floor(),
+        };
+        remaining = (remaining - amount * factor).max(0.0);
+        result.push(amount.copysign(value));
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn iso_duration() {
+        for (input, expected) in [
+            ("PT1H30M", 5400.0),
+            ("P1DT1H1M1S", 90061.0),
+            ("P2W", 1209600.0),
+            ("PT0.5S", 0.5),
+            ("P1Y", 31556952.0),
+            ("P1M", 2629746.0),
+        ] {
+            assert_eq!(Ok(("", expected)), parse_iso_duration(input), "{}", input);
+        }
+    }
+
+    #[test]
+    fn empty_iso_duration() {
+        assert!(parse_iso_duration("P").is_err());
+        assert!(parse_iso_duration("PT").is_err());
+        assert!(parse_iso_duration("PinfD").is_err());
+        assert!(parse_iso_duration("PTNaNS").is_err());
+    }
+
+    #[test]
+    fn components() {
+        assert_eq!(
+            vec![1.0, 1.0, 1.0, 1.0],
+            split_components(90061.0, &[86400.0, 3600.0, 60.0, 1.0])
+        );
+        assert_eq!(vec![-2.0, -30.0], split_components(-150.0, &[60.0, 1.0]));
+        assert_eq!(vec![0.0, 1.5], split_components(1.5, &[60.0, 1.0]));
+    }
+}
@@ -0,0 +1,583 @@
+use std::fmt::Display;
+use std::ops::RangeInclusive;
+
+use crate::angle::format_dms;
+use crate::converter::UnitConversion;
+use crate::parser::UnitAbbreviation;
+use crate::uncertainty::uncertainty_place;
+
+/// How the value of a result is written.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum Notation {
+    /// Fixed notation, switching to scientific notation for very large or small values.
+    #[default]
+    Auto,
+    /// Fixed notation (i.e. 1200000).
+    Fixed,
+    /// Scientific notation (i.e. 1.2e6).
+    Scientific,
+    /// Scientific notation with an exponent that is a multiple of 3 (i.e. 12e3), matching the SI
+    /// prefixes.
+    Engineering,
+}
+
+/// How the unit of a result is written.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum UnitStyle {
+    /// The unit's abbreviation (i.e. "nmi").
+    Symbol,
+    /// The unit's name in words, which is singular for a value of 1 (i.e. "nautical mile") and
+    /// plural otherwise (i.e. "nautical miles").
+    #[default]
+    Name,
+}
+
+/// The value and unit of a formatted result, which are kept separate so they can be styled
+/// differently.
+#[derive(Debug, PartialEq, Clone)]
+pub struct FormattedResult {
+    pub value: String,
+    pub unit: String,
+}
+
+impl Display for FormattedResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.value, self.unit)
+    }
+}
+
+/// The SI prefixes used when auto-scaling, which are limited to powers of 1000.
+const SI_PREFIXES: [(&str, i32); 10] = [
+    ("femto", -15),
+    ("pico", -12),
+    ("nano", -9),
+    ("micro", -6),
+    ("milli", -3),
+    ("kilo", 3),
+    ("mega", 6),
+    ("giga", 9),
+    ("tera", 12),
+    ("peta", 15),
+];
+
+/// Formats conversion results for display.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ResultFormatter {
+    notation: Notation,
+    precision: Option<usize>,
+    unit_style: UnitStyle,
+    auto_scale: bool,
+    fixed_range: RangeInclusive<f64>,
+}
+
+impl Default for ResultFormatter {
+    fn default() -> Self {
+        ResultFormatter {
+            notation: Notation::default(),
+            precision: None,
+            unit_style: UnitStyle::default(),
+            auto_scale: false,
+            fixed_range: 0.00009..=99999.0,
+        }
+    }
+}
+
+impl ResultFormatter {
+    pub fn new() -> ResultFormatter {
+        ResultFormatter::default()
+    }
+
+    pub fn notation(mut self, notation: Notation) -> ResultFormatter {
+        self.notation = notation;
+        self
+    }
+
+    /// Sets the number of decimal places, of the mantissa when using scientific or engineering
+    /// notation. Defaults to the significant figures of the result when it was rounded, or
+    /// otherwise to as many as are needed to represent the value.
+    pub fn precision(mut self, precision: usize) -> ResultFormatter {
+        self.precision = Some(precision);
+        self
+    }
+
+    pub fn unit_style(mut self, style: UnitStyle) -> ResultFormatter {
+        self.unit_style = style;
+        self
+    }
+
+    /// Sets the smallest and largest absolute values written in fixed notation when using the auto
+    /// notation, which defaults to 0.00009 to 99999.
+    pub fn fixed_range(mut self, min: f64, max: f64) -> ResultFormatter {
+        self.fixed_range = min..=max;
+        self
+    }
+
+    /// Converts results to the SI prefixed unit of the same type that gives the most readable
+    /// value, which is the largest unit for which the value is at least 1 (i.e. 1.2 Mm rather
+    /// than 1200000 m).
+    pub fn auto_scale(mut self, auto_scale: bool) -> ResultFormatter {
+        self.auto_scale = auto_scale;
+        self
+    }
+
+    /// Formats a result, using the unit definitions to find the abbreviation and prefixed
+    /// variants of its unit. Results with an exact value (i.e. "FF") or in degrees, minutes and
+    /// seconds are given as text. Uncertainties are formatted like the value and, unless a
+    /// precision was set, rounded to the same decimal place (i.e. "4.92 ± 0.08").
+    pub fn format(&self, result: &UnitConversion, units: &[UnitAbbreviation]) -> FormattedResult {
+        let unit_type = result.to_type.as_ref().unwrap_or(&result.unit_type);
+        let value = result.rounded.unwrap_or(result.value);
+        let text = match (&result.exact, &result.dms, result.uncertainty) {
+            (Some(exact), _, _) => Some(exact.to_string()),
+            (None, Some(dms), Some(uncertainty)) => {
+                Some(format!("{} ± {}", dms, format_dms(uncertainty)))
+            }
+            (None, Some(dms), None) => Some(dms.to_owned()),
+            (None, None, _) => None,
+        };
+        if let Some(text) = text {
+            return FormattedResult {
+                value: text,
+                unit: self.format_unit(&result.to, unit_type, value, units),
+            };
+        }
+
+        let (scaled, unit) = match self.auto_scale {
+            true => scale_to_prefix(value, &result.to, unit_type, units),
+            false => (value, result.to.as_str()),
+        };
+        let value = match result.uncertainty {
+            Some(uncertainty) => {
+                let factor = if value == 0.0 { 1.0 } else { scaled / value };
+                self.format_uncertainty(scaled, uncertainty * factor)
+            }
+            None => self.format_value(scaled, result.significant_figures),
+        };
+        FormattedResult {
+            value,
+            unit: self.format_unit(unit, unit_type, scaled, units),
+        }
+    }
+
+    /// Formats a value and its uncertainty, rounding both to the decimal place of the
+    /// uncertainty's leading figures when no precision was set.
+    fn format_uncertainty(&self, value: f64, uncertainty: f64) -> String {
+        let place = uncertainty_place(uncertainty).filter(|_| self.precision.is_none());
+        let Some(place) = place else {
+            return format!(
+                "{} ± {}",
+                self.format_value(value, None),
+                self.format_value(uncertainty, None)
+            );
+        };
+        let round = |x: f64| (x / 10f64.powi(place)).round() * 10f64.powi(place);
+        let figures = |x: f64| match x == 0.0 {
+            true => Some(1),
+            false => Some((x.abs().log10().floor() as i32 - place + 1).max(1) as u32),
+        };
+        let (value, uncertainty) = (round(value), round(uncertainty));
+        format!(
+            "{} ± {}",
+            self.format_value(value, figures(value)),
+            self.format_value(uncertainty, figures(uncertainty))
+        )
+    }
+
+    /// Formats a value using the notation and precision, or the number of significant figures
+    /// when no precision was set.
+    pub fn format_value(&self, value: f64, significant_figures: Option<u32>) -> String {
+        if !value.is_finite() {
+            return value.to_string();
+        }
+
+        let notation = match self.notation {
+            Notation::Auto if value != 0.0 && !self.fixed_range.contains(&value.abs()) => {
+                Notation::Scientific
+            }
+            Notation::Auto => Notation::Fixed,
+            notation => notation,
+        };
+        let magnitude = match value == 0.0 {
+            true => 0,
+            false => value.abs().log10().floor() as i32,
+        };
+        // The number of decimal places needed for the significant figures, relative to the
+        // magnitude of the written value.
+        let decimals = |written_magnitude: i32| {
+            self.precision.or(significant_figures
+                .map(|figures| (figures as i32 - 1 - written_magnitude).max(0) as usize))
+        };
+
+        match notation {
+            Notation::Auto | Notation::Fixed => match decimals(magnitude) {
+                Some(decimals) => format!("{:.*}", decimals, value),
+                None => value.to_string(),
+            },
+            Notation::Scientific => match decimals(0) {
+                Some(decimals) => format!("{:.*e}", decimals, value),
+                None => format!("{:e}", value),
+            },
+            Notation::Engineering => {
+                let mut exponent = magnitude.div_euclid(3) * 3;
+                let mut mantissa = value / 10f64.powi(exponent);
+                let mut decimals = decimals(magnitude - exponent);
+                // Rounding can carry the mantissa over to the next power of 1000 (i.e. 999.96
+                // to 1000.0).
+                if let Some(places) = decimals {
+                    let rounded = format!("{:.*}", places, mantissa).parse::<f64>();
+                    if rounded.is_ok_and(|m| m.abs() >= 1000.0) {
+                        exponent += 3;
+                        mantissa /= 1000.0;
+                        decimals = self.precision.or(Some(places + 3));
+                    }
+                }
+                match decimals {
+                    Some(decimals) => format!("{:.*}e{}", decimals, mantissa, exponent),
+                    // Shifting the decimal point of the shortest scientific representation
+                    // avoids the rounding errors of dividing by a power of 10.
+                    None => {
+                        let scientific = format!("{:e}", value);
+                        let (digits, _) = scientific.split_once('e').unwrap_or((&scientific, ""));
+                        let shift = (magnitude - exponent) as usize;
+                        format!("{}e{}", shift_decimal_point(digits, shift), exponent)
+                    }
+                }
+            }
+        }
+    }
+
+    fn format_unit(
+        &self,
+        unit: &str,
+        unit_type: &str,
+        value: f64,
+        units: &[UnitAbbreviation],
+    ) -> String {
+        let plural = value.abs() != 1.0;
+        match self.unit_style {
+            UnitStyle::Symbol => units
+                .iter()
+                .find(|u| u.unit == unit && u.unit_type == unit_type)
+                .map_or_else(|| unit_name(unit, plural), |u| u.abbrev.to_owned()),
+            UnitStyle::Name => unit_name(unit, plural),
+        }
+    }
+}
+
+/// Moves the decimal point of a number right by a number of places, padding it with zeros (i.e.
+/// "2.5" to "250").
+fn shift_decimal_point(number: &str, places: usize) -> String {
+    let (sign, number) = match number.strip_prefix('-') {
+        Some(number) => ("-", number),
+        None => ("", number),
+    };
+    let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+    let fraction = format!("{:0<width$}", fraction, width = places);
+    let (shifted, rest) = fraction.split_at(places);
+    match rest.is_empty() {
+        true => format!("{}{}{}", sign, whole, shifted),
+        false => format!("{}{}{}.{}", sign, whole, shifted, rest),
+    }
+}
+
+/// Converts a value to the SI prefixed variant of its unit that gives the most readable value,
+/// returning the value unchanged if the unit has no prefixed variants.
+fn scale_to_prefix<'a>(
+    value: f64,
+    unit: &'a str,
+    unit_type: &str,
+    units: &'a [UnitAbbreviation],
+) -> (f64, &'a str) {
+    let (power, base) = split_prefix(unit);
+    let mut candidates = units
+        .iter()
+        .filter(|u| u.unit_type == unit_type)
+        .map(|u| (split_prefix(&u.unit), u.unit.as_str()))
+        .filter(|((_, b), _)| b.eq_ignore_ascii_case(base))
+        .map(|((p, _), name)| (p, name))
+        .collect::<Vec<_>>();
+    if value == 0.0 || candidates.len() < 2 {
+        return (value, unit);
+    }
+
+    candidates.sort_by_key(|(p, _)| *p);
+    candidates.dedup_by_key(|(p, _)| *p);
+    let magnitude = value.abs().log10() + power as f64;
+    let (scaled_power, scaled_unit) = candidates
+        .iter()
+        .rev()
+        .find(|(p, _)| magnitude >= *p as f64)
+        .unwrap_or(&candidates[0]);
+    // Dividing by a power of 10 avoids rounding errors from negative powers (i.e. 1e-3).
+    let scaled = match power - scaled_power {
+        shift if shift < 0 => value / 10f64.powi(-shift),
+        shift => value * 10f64.powi(shift),
+    };
+    (scaled, scaled_unit)
+}
+
+/// Splits the SI prefix from a unit name, returning its power of 10 (i.e. (3, "meters") for
+/// "Kilometers").
+fn split_prefix(unit: &str) -> (i32, &str) {
+    SI_PREFIXES
+        .iter()
+        .find_map(|(prefix, power)| {
+            let rest = unit.get(prefix.len()..)?;
+            (unit[..prefix.len()].eq_ignore_ascii_case(prefix) && !rest.is_empty())
+                .then_some((*power, rest))
+        })
+        .unwrap_or((0, unit))
+}
+
+/// Writes a unit name in words (i.e. "nautical miles" for "NauticalMiles"). Unit names are
+/// plural, so the singular is formed from the last word, or the word before "per" (i.e. "mile
+/// per gallon").
+fn unit_name(unit: &str, plural: bool) -> String {
+    let mut words = split_words(unit);
+    if !plural {
+        let index = words
+            .iter()
+            .position(|w| w == "per")
+            .unwrap_or(words.len())
+            .saturating_sub(1);
+        if let Some(word) = words.get_mut(index) {
+            *word = singular(word);
+        }
+    }
+    words.join(" ")
+}
+
+/// Splits a camel case name into lowercase words, keeping acronyms (i.e. "US") and numbers as
+/// separate words.
+fn split_words(name: &str) -> Vec<String> {
+    let chars = name.chars().collect::<Vec<_>>();
+    let mut words = vec![];
+    let mut word = String::new();
+    for (i, c) in chars.iter().enumerate() {
+        let boundary = match (i.checked_sub(1).map(|p| chars[p]), chars.get(i + 1)) {
+            (None, _) => false,
+            (Some(prev), next) => {
+                (c.is_uppercase() && !prev.is_uppercase())
+                    || (c.is_uppercase() && next.is_some_and(|n| n.is_lowercase()))
+                    || c.is_ascii_digit() != prev.is_ascii_digit()
+            }
+        };
+        if boundary && !word.is_empty() {
+            words.push(std::mem::take(&mut word));
+        }
+        word.push(*c);
+    }
+    words.push(word);
+
+    words
+        .into_iter()
+        .map(
+            |w| match w.len() > 1 && w.chars().all(|c| !c.is_lowercase()) {
+                true => w,
+                false => w.to_lowercase(),
+            },
+        )
+        .collect()
+}
+
+fn singular(word: &str) -> String {
+    if word == "feet" {
+        return String::from("foot");
+    }
+    if let Some(stem) = word.strip_suffix("ies") {
+        return format!("{}y", stem);
+    }
+    for suffix in ["ches", "shes", "xes"] {
+        if word.ends_with(suffix) {
+            return word[..word.len() - 2].to_owned();
+        }
+    }
+    match word.strip_suffix('s') {
+        Some(stem) if !word.ends_with("ss") && !word.ends_with("us") => stem.to_owned(),
+        _ => word.to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn construct_units() -> Vec<UnitAbbreviation> {
+        [
+            ("Megameters", "Mm"),
+            ("Kilometers", "km"),
+            ("Meters", "m"),
+            ("Millimeters", "mm"),
+            ("NauticalMiles", "nmi"),
+        ]
+        .iter()
+        .map(|(unit, abbrev)| UnitAbbreviation {
+            unit: unit.to_string(),
+            abbrev: abbrev.to_string(),
+            unit_type: String::from("Length"),
+            region: None,
+        })
+        .collect()
+    }
+
+    fn construct_result(value: f64, to: &str) -> UnitConversion {
+        UnitConversion {
+            value,
+            to: to.to_owned(),
+            unit_type: String::from("Length"),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn notation() {
+        let formatter = ResultFormatter::new();
+        assert_eq!("1.25", formatter.format_value(1.25, None));
+        assert_eq!("1.2e6", formatter.format_value(1200000.0, None));
+        assert_eq!("0", formatter.format_value(0.0, None));
+
+        let formatter = ResultFormatter::new().notation(Notation::Fixed);
+        assert_eq!("1200000", formatter.format_value(1200000.0, None));
+
+        let formatter = ResultFormatter::new().notation(Notation::Scientific);
+        assert_eq!("1.2e6", formatter.format_value(1200000.0, None));
+        assert_eq!("1.20e6", formatter.format_value(1200000.0, Some(3)));
+
+        let formatter = ResultFormatter::new().notation(Notation::Engineering);
+        assert_eq!("1.2e6", formatter.format_value(1200000.0, None));
+        assert_eq!("12e3", formatter.format_value(12000.0, None));
+        assert_eq!("-250e-6", formatter.format_value(-0.00025, None));
+        assert_eq!("120.0e3", formatter.format_value(120000.0, Some(4)));
+    }
+
+    #[test]
+    fn precision() {
+        let formatter = ResultFormatter::new().precision(2);
+        assert_eq!("1.08", formatter.format_value(1.0799136069114472, None));
+        assert_eq!("1.08", formatter.format_value(1.0799136069114472, Some(1)));
+
+        let formatter = ResultFormatter::new();
+        assert_eq!("1.080", formatter.format_value(1.08, Some(4)));
+        assert_eq!("1200", formatter.format_value(1200.0, Some(2)));
+
+        let formatter = ResultFormatter::new()
+            .notation(Notation::Engineering)
+            .precision(1);
+        assert_eq!("1.0e3", formatter.format_value(999.96, None));
+    }
+
+    #[test]
+    fn unit_styles() {
+        let units = construct_units();
+        let formatter = ResultFormatter::new();
+        assert_eq!(
+            "2 nautical miles",
+            formatter
+                .format(&construct_result(2.0, "NauticalMiles"), &units)
+                .to_string()
+        );
+        assert_eq!(
+            "1 nautical mile",
+            formatter
+                .format(&construct_result(1.0, "NauticalMiles"), &units)
+                .to_string()
+        );
+
+        let formatter = ResultFormatter::new().unit_style(UnitStyle::Symbol);
+        assert_eq!(
+            "2 nmi",
+            formatter
+                .format(&construct_result(2.0, "NauticalMiles"), &units)
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn fixed_range() {
+        let formatter = ResultFormatter::new().fixed_range(0.001, 1e9);
+        assert_eq!("1200000", formatter.format_value(1200000.0, None));
+        assert_eq!("1e-4", formatter.format_value(0.0001, None));
+    }
+
+    #[test]
+    fn uncertainty() {
+        let units = construct_units();
+        let result = UnitConversion {
+            uncertainty: Some(0.07874015748031496),
+            ..construct_result(4.921259842519685, "Meters")
+        };
+        for (formatter, expected) in [
+            (ResultFormatter::new(), "4.92 ± 0.08 meters"),
+            (ResultFormatter::new().precision(3), "4.921 ± 0.079 meters"),
+            (
+                ResultFormatter::new().notation(Notation::Scientific),
+                "4.92e0 ± 8e-2 meters",
+            ),
+            (
+                ResultFormatter::new()
+                    .auto_scale(true)
+                    .unit_style(UnitStyle::Symbol),
+                "4.92 ± 0.08 m",
+            ),
+        ] {
+            assert_eq!(expected, formatter.format(&result, &units).to_string());
+        }
+
+        let result = UnitConversion {
+            uncertainty: Some(20.0),
+            ..construct_result(1234.0, "Meters")
+        };
+        let formatter = ResultFormatter::new()
+            .auto_scale(true)
+            .unit_style(UnitStyle::Symbol);
+        assert_eq!(
+            "1.23 ± 0.02 km",
+            formatter.format(&result, &units).to_string()
+        );
+    }
+
+    #[test]
+    fn dms_with_uncertainty() {
+        let result = UnitConversion {
+            uncertainty: Some(0.1),
+            formatted_uncertainty: Some(String::from("45.50 ± 0.10")),
+            dms: Some(String::from("45°30'0\"")),
+            ..construct_result(45.5, "Degrees")
+        };
+        let actual = ResultFormatter::new().format(&result, &construct_units());
+        assert_eq!("45°30'0\" ± 0°6'0\"", actual.value);
+    }
+
+    #[test]
+    fn unit_names() {
+        assert_eq!("feet per second", unit_name("FeetPerSecond", true));
+        assert_eq!("foot per second", unit_name("FeetPerSecond", false));
+        assert_eq!("US gallon", unit_name("USGallons", false));
+        assert_eq!(
+            "liters per 100 kilometers",
+            unit_name("LitersPer100Kilometers", true)
+        );
+        assert_eq!("inch", unit_name("Inches", false));
+        assert_eq!("century", unit_name("Centuries", false));
+        assert_eq!("celsius", unit_name("Celsius", false));
+    }
+
+    #[test]
+    fn auto_scale() {
+        let units = construct_units();
+        let formatter = ResultFormatter::new()
+            .auto_scale(true)
+            .unit_style(UnitStyle::Symbol);
+        for (value, to, expected) in [
+            (1200000.0, "Meters", "1.2 Mm"),
+            (1200.0, "Meters", "1.2 km"),
+            (0.5, "Kilometers", "500 m"),
+            (0.0025, "Meters", "2.5 mm"),
+            (0.0000025, "Meters", "0.0025 mm"),
+            (2.0, "NauticalMiles", "2 nmi"),
+        ] {
+            let actual = formatter.format(&construct_result(value, to), &units);
+            assert_eq!(expected, actual.to_string(), "{} {}", value, to);
+        }
+    }
+}
@@ -1,4 +1,5 @@
-use std::collections::VecDeque;
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
 use std::fmt::Debug;
 
 use log::{debug, warn};
@@ -30,6 +31,7 @@ struct NodeData<T> {
 
 #[derive(Debug)]
 struct EdgeData<T> {
+    source: NodeIndex,
     target: NodeIndex,
     weight: T,
 }
@@ -98,7 +100,11 @@ where
         let edge_index = self.edges.len();
         let node_data = &mut self.nodes[source];
 
-        self.edges.push(EdgeData { target, weight });
+        self.edges.push(EdgeData {
+            source,
+            target,
+            weight,
+        });
 
         node_data.edges.push(edge_index);
         Ok(())
@@ -113,6 +119,19 @@ where
         None
     }
 
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// Returns all edges in the graph, in the order they were added.
+    pub fn edges(&self) -> impl Iterator<Item = GraphEdge<'_, N, E>> {
+        self.edges.iter().map(|edge| GraphEdge {
+            source: &self.nodes[edge.source].value,
+            target: &self.nodes[edge.target].value,
+            weight: &edge.weight,
+        })
+    }
+
     pub fn get_edge_weight(&self, source: NodeIndex, target: NodeIndex) -> Option<&E> {
         debug!(
             "Getting edge weight between nodes {} [{:?}] and {} [{:?}]",
@@ -142,28 +161,50 @@ where
         None
     }
 
-    pub fn shortest_path(&self, source: NodeIndex, target: NodeIndex) -> Vec<GraphEdge<N, E>> {
-        let mut queue = VecDeque::new();
+    /// Finds the path between two nodes with the lowest total cost, using the cost of each edge,
+    /// and the fewest edges when several paths have the same cost. Remaining ties are broken in
+    /// favour of nodes that were added first, so the same path is always returned.
+    pub fn shortest_path(
+        &self,
+        source: NodeIndex,
+        target: NodeIndex,
+        cost: impl Fn(&E) -> f64,
+    ) -> Vec<GraphEdge<'_, N, E>> {
         let mut visited = vec![false; self.nodes.len()];
-        let mut dist = vec![usize::MAX; self.nodes.len()];
+        let mut dist = vec![(f64::INFINITY, usize::MAX); self.nodes.len()];
         let mut path = vec![None; self.nodes.len()];
+        let mut queue = BinaryHeap::new();
+        dist[source] = (0.0, 0);
+        queue.push(Reverse(QueueEntry {
+            cost: 0.0,
+            hops: 0,
+            node: source,
+        }));
+
+        while let Some(Reverse(QueueEntry { node, .. })) = queue.pop() {
+            // A node is queued again each time a shorter path to it is found, so the stale
+            // entries are skipped.
+            if visited[node] {
+                continue;
+            }
+            if node == target {
+                break;
+            }
+            visited[node] = true;
 
-        visited[source] = true;
-        dist[source] = 0;
-        queue.push_back(source);
-
-        while let Some(node) = queue.pop_front() {
             for edge in &self.nodes[node].edges {
                 let edge_data = &self.edges[*edge];
-                if !visited[edge_data.target] {
-                    visited[edge_data.target] = true;
-                    dist[edge_data.target] = dist[node] + 1;
+                let candidate = (dist[node].0 + cost(&edge_data.weight), dist[node].1 + 1);
+                if !visited[edge_data.target]
+                    && compare_distance(candidate, dist[edge_data.target]) == Ordering::Less
+                {
+                    dist[edge_data.target] = candidate;
                     path[edge_data.target] = Some((node, edge_data.target, &edge_data.weight));
-
-                    queue.push_back(edge_data.target);
-                    if edge_data.target == target {
-                        break;
-                    }
+                    queue.push(Reverse(QueueEntry {
+                        cost: candidate.0,
+                        hops: candidate.1,
+                        node: edge_data.target,
+                    }));
                 }
             }
         }
@@ -186,6 +227,40 @@ where
     }
 }
 
+/// Orders distances by their cost and then their number of edges.
+fn compare_distance(a: (f64, usize), b: (f64, usize)) -> Ordering {
+    a.0.total_cmp(&b.0).then(a.1.cmp(&b.1))
+}
+
+/// A node waiting to be visited by `shortest_path`. Entries are ordered by their distance and
+/// then by the node index, so that paths with equal costs are chosen the same way every time.
+struct QueueEntry {
+    cost: f64,
+    hops: usize,
+    node: NodeIndex,
+}
+
+impl Ord for QueueEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        compare_distance((self.cost, self.hops), (other.cost, other.hops))
+            .then(self.node.cmp(&other.node))
+    }
+}
+
+impl PartialOrd for QueueEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for QueueEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for QueueEntry {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -264,6 +339,34 @@ mod tests {
         assert_eq!(actual, n0);
     }
 
+    #[test]
+    fn edges_in_insertion_order() {
+        let mut graph = Graph::default();
+
+        let n0 = graph.add_node(1);
+        let n1 = graph.add_node(2);
+        let n2 = graph.add_node(3);
+
+        _ = graph.add_edge(n1, n2, 10);
+        _ = graph.add_edge(n0, n1, 5);
+
+        let expected: Vec<GraphEdge<i32, i32>> = vec![
+            GraphEdge {
+                source: &2,
+                target: &3,
+                weight: &10,
+            },
+            GraphEdge {
+                source: &1,
+                target: &2,
+                weight: &5,
+            },
+        ];
+        let actual: Vec<GraphEdge<i32, i32>> = graph.edges().collect();
+        assert_eq!(expected, actual);
+        assert_eq!(2, graph.edge_count());
+    }
+
     #[test]
     fn shortest_path_dijkstra() {
         let mut graph = Graph::default();
@@ -292,7 +395,7 @@ mod tests {
                 weight: &30,
             },
         ];
-        let actual = graph.shortest_path(n0, n3);
+        let actual = graph.shortest_path(n0, n3, |_| 0.0);
         assert_eq!(expected, actual);
     }
 
@@ -313,7 +416,7 @@ mod tests {
             target: &3,
             weight: &25,
         }];
-        let actual = graph.shortest_path(n0, n2);
+        let actual = graph.shortest_path(n0, n2, |_| 0.0);
         assert_eq!(expected, actual);
     }
 
@@ -330,7 +433,7 @@ mod tests {
             target: &2,
             weight: &30,
         }];
-        let actual = graph.shortest_path(n0, n1);
+        let actual = graph.shortest_path(n0, n1, |_| 0.0);
         assert_eq!(expected, actual);
     }
 
@@ -342,7 +445,7 @@ mod tests {
         let n1 = graph.add_node(2);
 
         let expected: Vec<GraphEdge<i32, i32>> = vec![];
-        let actual = graph.shortest_path(n0, n1);
+        let actual = graph.shortest_path(n0, n1, |_| 0.0);
         assert_eq!(expected, actual);
     }
 
@@ -359,7 +462,7 @@ mod tests {
         _ = graph.add_edge(n3, n2, 20);
 
         let expected: Vec<GraphEdge<i32, i32>> = vec![];
-        let actual = graph.shortest_path(n3, n0);
+        let actual = graph.shortest_path(n3, n0, |_| 0.0);
         assert_eq!(expected, actual);
     }
 
@@ -375,7 +478,54 @@ mod tests {
         _ = graph.add_edge(n1, n2, 30);
 
         let expected: Vec<GraphEdge<i32, i32>> = vec![];
-        let actual = graph.shortest_path(n0, n1);
+        let actual = graph.shortest_path(n0, n1, |_| 0.0);
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn shortest_path_weighted() {
+        let mut graph = Graph::default();
+
+        let n0 = graph.add_node(1);
+        let n1 = graph.add_node(2);
+        let n2 = graph.add_node(3);
+
+        _ = graph.add_edge(n0, n2, 25);
+        _ = graph.add_edge(n0, n1, 5);
+        _ = graph.add_edge(n1, n2, 10);
+
+        let expected: Vec<GraphEdge<i32, i32>> = vec![
+            GraphEdge {
+                source: &1,
+                target: &2,
+                weight: &5,
+            },
+            GraphEdge {
+                source: &2,
+                target: &3,
+                weight: &10,
+            },
+        ];
+        let actual = graph.shortest_path(n0, n2, |w| *w as f64);
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn shortest_path_ties_use_node_order() {
+        let mut graph = Graph::default();
+
+        let n0 = graph.add_node(1);
+        let n1 = graph.add_node(2);
+        let n2 = graph.add_node(3);
+        let n3 = graph.add_node(4);
+
+        _ = graph.add_edge(n0, n2, 5);
+        _ = graph.add_edge(n0, n1, 5);
+        _ = graph.add_edge(n1, n3, 5);
+        _ = graph.add_edge(n2, n3, 5);
+
+        let actual = graph.shortest_path(n0, n3, |w| *w as f64);
+        assert_eq!(&2, actual[0].target);
+        assert_eq!(2, actual.len());
+    }
 }
@@ -1,10 +1,28 @@
 use std::fmt::Display;
 
+use log_scale::LogScale;
+use table::LookupTable;
+
+mod angle;
 pub mod converter;
+mod currency;
+mod data;
+mod duration;
+pub mod format;
 mod graph;
+mod log_scale;
+mod numeral;
 mod parser;
+mod precision;
+mod rational;
 pub mod source;
+mod substance;
+mod table;
+mod timezone;
+mod ucum;
+mod uncertainty;
 
+#[derive(Debug)]
 pub struct ConversionDefinition {
     category: String,
     from: String,
@@ -15,14 +33,38 @@ pub struct ConversionDefinition {
 #[derive(Debug, PartialEq, Clone)]
 pub enum ConversionValueDefinition {
     Multiplier(f64),
-    Expression(String),
+    /// A multiplier that has been rounded (i.e. 39.3701 inches per meter), which limits the
+    /// significant figures of results.
+    Approximate(f64),
+    Reciprocal(f64),
+    Expression {
+        expr: String,
+        /// Default values for parameters used by the expression other than the source unit.
+        params: Vec<(String, f64)>,
+    },
+    Table(LookupTable),
+    Logarithmic(LogScale),
 }
 
 impl Display for ConversionValueDefinition {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Multiplier(val) => f.write_fmt(format_args!("{}", val)),
-            Self::Expression(expr) => f.write_str(expr),
+            Self::Approximate(val) => f.write_fmt(format_args!("~{}", val)),
+            Self::Reciprocal(val) => f.write_fmt(format_args!("{} / x", val)),
+            Self::Expression { expr, params } => {
+                f.write_str(expr)?;
+                for (name, default) in params {
+                    f.write_fmt(format_args!(", {} = {}", name, default))?;
+                }
+                Ok(())
+            }
+            Self::Table(table) => f.write_fmt(format_args!(
+                "{} table of {} points",
+                table.interpolation,
+                table.points.len()
+            )),
+            Self::Logarithmic(scale) => f.write_fmt(format_args!("{}", scale)),
         }
     }
 }
@@ -0,0 +1,147 @@
+use std::fmt::Display;
+
+use crate::converter::error::ConversionError;
+
+/// A logarithmic unit defined relative to a linear unit, where a level `L` is equal to
+/// `factor * log_base(x / reference)`. Converting from the level to the linear unit uses
+/// `reference * base ^ (L / factor)`.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct LogScale {
+    pub base: f64,
+    pub factor: f64,
+    pub reference: f64,
+    /// Converts from the linear unit to the level rather than from the level.
+    pub inverse: bool,
+}
+
+impl LogScale {
+    pub fn new(base: f64, factor: f64, reference: f64) -> Result<LogScale, ConversionError> {
+        if base <= 0.0 || base == 1.0 {
+            return Err(ConversionError::new(
+                "Logarithmic scales must have a positive base other than 1",
+            ));
+        }
+        if factor == 0.0 {
+            return Err(ConversionError::new(
+                "Logarithmic scales must have a non-zero factor",
+            ));
+        }
+        if reference <= 0.0 {
+            return Err(ConversionError::new(
+                "Logarithmic scales must have a positive reference level",
+            ));
+        }
+        Ok(LogScale {
+            base,
+            factor,
+            reference,
+            inverse: false,
+        })
+    }
+
+    pub fn inverse(&self) -> LogScale {
+        LogScale {
+            inverse: !self.inverse,
+            ..*self
+        }
+    }
+
+    pub fn convert(&self, value: f64) -> Result<f64, ConversionError> {
+        match self.inverse {
+            true => self.level(value),
+            false => Ok(self.linear(value)),
+        }
+    }
+
+    pub fn linear(&self, level: f64) -> f64 {
+        self.reference * self.base.powf(level / self.factor)
+    }
+
+    pub fn level(&self, value: f64) -> Result<f64, ConversionError> {
+        if value <= 0.0 {
+            return Err(ConversionError::new(&format!(
+                "Unable to convert {} to a logarithmic unit as it is not positive",
+                value
+            )));
+        }
+        Ok(self.factor * (value / self.reference).log(self.base))
+    }
+
+    /// Adds levels together. Levels can't be summed directly, so they are converted to the
+    /// linear unit, summed and converted back (i.e. 3 dB + 3 dB = 6.01 dB).
+    pub fn sum(&self, levels: &[f64]) -> Result<f64, ConversionError> {
+        self.level(levels.iter().map(|level| self.linear(*level)).sum())
+    }
+}
+
+impl Display for LogScale {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.inverse {
+            true => write!(
+                f,
+                "{} log{}(x / {})",
+                self.factor, self.base, self.reference
+            ),
+            false => write!(
+                f,
+                "{} * {}^(x / {})",
+                self.reference, self.base, self.factor
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_approx_eq(expected: f64, actual: f64) {
+        assert!(
+            (expected - actual).abs() < 1e-9,
+            "Expected {} but was {}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn level_to_linear() {
+        let dbm = LogScale::new(10.0, 10.0, 0.001).unwrap();
+        assert_approx_eq(1.0, dbm.convert(30.0).unwrap());
+        assert_approx_eq(0.001, dbm.convert(0.0).unwrap());
+    }
+
+    #[test]
+    fn linear_to_level() {
+        let dbv = LogScale::new(10.0, 20.0, 1.0).unwrap().inverse();
+        assert_approx_eq(20.0, dbv.convert(10.0).unwrap());
+        assert_approx_eq(-20.0, dbv.convert(0.1).unwrap());
+    }
+
+    #[test]
+    fn negative_factor() {
+        let ph = LogScale::new(10.0, -1.0, 1.0).unwrap().inverse();
+        assert_approx_eq(7.0, ph.convert(1e-7).unwrap());
+    }
+
+    #[test]
+    fn non_positive_value_to_level() {
+        let db = LogScale::new(10.0, 10.0, 1.0).unwrap().inverse();
+        assert!(db.convert(0.0).is_err());
+        assert!(db.convert(-1.0).is_err());
+    }
+
+    #[test]
+    fn invalid_scale() {
+        assert!(LogScale::new(1.0, 10.0, 1.0).is_err());
+        assert!(LogScale::new(10.0, 0.0, 1.0).is_err());
+        assert!(LogScale::new(10.0, 10.0, 0.0).is_err());
+    }
+
+    #[test]
+    fn sum_levels() {
+        let db = LogScale::new(10.0, 10.0, 1.0).unwrap();
+        assert_approx_eq(10.0 * 2f64.log10() + 3.0, db.sum(&[3.0, 3.0]).unwrap());
+        assert_approx_eq(20.0, db.sum(&[20.0]).unwrap());
+    }
+}
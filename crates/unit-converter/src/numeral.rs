@@ -0,0 +1,218 @@
+use crate::converter::error::ConversionError;
+
+pub const NUMERAL_UNIT_TYPE: &str = "Numeral";
+
+const ROMAN_NUMERALS: [(i128, &str); 13] = [
+    (1000, "M"),
+    (900, "CM"),
+    (500, "D"),
+    (400, "CD"),
+    (100, "C"),
+    (90, "XC"),
+    (50, "L"),
+    (40, "XL"),
+    (10, "X"),
+    (9, "IX"),
+    (5, "V"),
+    (4, "IV"),
+    (1, "I"),
+];
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum NumeralSystem {
+    Binary,
+    Octal,
+    Decimal,
+    Hexadecimal,
+    Roman,
+}
+
+impl NumeralSystem {
+    pub const ALL: [NumeralSystem; 5] = [
+        NumeralSystem::Binary,
+        NumeralSystem::Octal,
+        NumeralSystem::Decimal,
+        NumeralSystem::Hexadecimal,
+        NumeralSystem::Roman,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            NumeralSystem::Binary => "Binary",
+            NumeralSystem::Octal => "Octal",
+            NumeralSystem::Decimal => "Decimal",
+            NumeralSystem::Hexadecimal => "Hexadecimal",
+            NumeralSystem::Roman => "Roman",
+        }
+    }
+
+    pub fn abbrev(&self) -> &'static str {
+        match self {
+            NumeralSystem::Binary => "bin",
+            NumeralSystem::Octal => "oct",
+            NumeralSystem::Decimal => "dec",
+            NumeralSystem::Hexadecimal => "hex",
+            NumeralSystem::Roman => "roman",
+        }
+    }
+
+    pub fn from_abbrev(input: &str) -> Option<NumeralSystem> {
+        NumeralSystem::ALL
+            .into_iter()
+            .find(|system| system.abbrev() == input)
+    }
+
+    fn radix(&self) -> Option<u32> {
+        match self {
+            NumeralSystem::Binary => Some(2),
+            NumeralSystem::Octal => Some(8),
+            NumeralSystem::Decimal => Some(10),
+            NumeralSystem::Hexadecimal => Some(16),
+            NumeralSystem::Roman => None,
+        }
+    }
+
+    /// Determines the numeral system of a value without an explicit system from its prefix (i.e.
+    /// "0x" or "0b"), treating values made up of Roman numerals as Roman and anything else as
+    /// decimal. Returns the system and the value without its prefix.
+    pub fn detect(input: &str) -> (NumeralSystem, String) {
+        let (sign, digits) = match input.strip_prefix('-') {
+            Some(digits) => ("-", digits),
+            None => ("", input),
+        };
+        for (prefix, system) in [
+            ("0b", NumeralSystem::Binary),
+            ("0o", NumeralSystem::Octal),
+            ("0x", NumeralSystem::Hexadecimal),
+        ] {
+            if digits.to_lowercase().starts_with(prefix) {
+                return (system, format!("{}{}", sign, &digits[prefix.len()..]));
+            }
+        }
+
+        let roman = !input.is_empty() && input.chars().all(|c| "IVXLCDM".contains(c));
+        match roman {
+            true => (NumeralSystem::Roman, input.to_owned()),
+            false => (NumeralSystem::Decimal, input.to_owned()),
+        }
+    }
+
+    pub fn parse(&self, input: &str) -> Result<i128, ConversionError> {
+        let invalid = || {
+            ConversionError::new(&format!(
+                "'{}' is not a valid {} number",
+                input,
+                self.name().to_lowercase()
+            ))
+        };
+
+        let Some(radix) = self.radix() else {
+            let value = parse_roman(&input.to_uppercase()).ok_or_else(invalid)?;
+            // Only accept numerals in their standard form (i.e. "IV" rather than "IIII").
+            return match self.format(value)? == input.to_uppercase() {
+                true => Ok(value),
+                false => Err(invalid()),
+            };
+        };
+        let digits = input.replace('_', "");
+        match digits.strip_prefix('-') {
+            Some(digits) => i128::from_str_radix(digits, radix).map(|value| -value),
+            None => i128::from_str_radix(&digits, radix),
+        }
+        .map_err(|_| invalid())
+    }
+
+    pub fn format(&self, value: i128) -> Result<String, ConversionError> {
+        let sign = if value < 0 { "-" } else { "" };
+        let magnitude = value.unsigned_abs();
+        match self {
+            NumeralSystem::Binary => Ok(format!("{}{:b}", sign, magnitude)),
+            NumeralSystem::Octal => Ok(format!("{}{:o}", sign, magnitude)),
+            NumeralSystem::Decimal => Ok(value.to_string()),
+            NumeralSystem::Hexadecimal => Ok(format!("{}{:X}", sign, magnitude)),
+            NumeralSystem::Roman => format_roman(value),
+        }
+    }
+}
+
+fn parse_roman(input: &str) -> Option<i128> {
+    let mut result = 0;
+    let mut rest = input;
+    while !rest.is_empty() {
+        let (value, numeral) = ROMAN_NUMERALS
+            .iter()
+            .find(|(_, numeral)| rest.starts_with(numeral))?;
+        result += value;
+        rest = &rest[numeral.len()..];
+    }
+    Some(result)
+}
+
+fn format_roman(value: i128) -> Result<String, ConversionError> {
+    if !(1..=3999).contains(&value) {
+        return Err(ConversionError::new(
+            "Roman numerals can only represent values from 1 to 3999",
+        ));
+    }
+
+    let mut result = String::new();
+    let mut remaining = value;
+    for (value, numeral) in ROMAN_NUMERALS {
+        while remaining >= value {
+            result.push_str(numeral);
+            remaining -= value;
+        }
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detect() {
+        for (input, system, digits) in [
+            ("0b1011", NumeralSystem::Binary, "1011"),
+            ("0o17", NumeralSystem::Octal, "17"),
+            ("0xFF", NumeralSystem::Hexadecimal, "FF"),
+            ("-0xff", NumeralSystem::Hexadecimal, "-ff"),
+            ("MCMXCIV", NumeralSystem::Roman, "MCMXCIV"),
+            ("255", NumeralSystem::Decimal, "255"),
+            ("-12", NumeralSystem::Decimal, "-12"),
+        ] {
+            let expected = (system, digits.to_owned());
+            assert_eq!(expected, NumeralSystem::detect(input), "{}", input);
+        }
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(11, NumeralSystem::Binary.parse("1011").unwrap());
+        assert_eq!(-255, NumeralSystem::Hexadecimal.parse("-ff").unwrap());
+        assert_eq!(1994, NumeralSystem::Roman.parse("MCMXCIV").unwrap());
+        assert_eq!(14, NumeralSystem::Roman.parse("xiv").unwrap());
+        assert_eq!(
+            u64::MAX as i128,
+            NumeralSystem::Hexadecimal
+                .parse("FFFF_FFFF_FFFF_FFFF")
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert!(NumeralSystem::Binary.parse("102").is_err());
+        assert!(NumeralSystem::Roman.parse("IIII").is_err());
+        assert!(NumeralSystem::Roman.parse("ABC").is_err());
+    }
+
+    #[test]
+    fn format() {
+        assert_eq!("FF", NumeralSystem::Hexadecimal.format(255).unwrap());
+        assert_eq!("-1011", NumeralSystem::Binary.format(-11).unwrap());
+        assert_eq!("MMXXIV", NumeralSystem::Roman.format(2024).unwrap());
+        assert!(NumeralSystem::Roman.format(0).is_err());
+        assert!(NumeralSystem::Roman.format(4000).is_err());
+    }
+}
@@ -1,102 +1,632 @@
+use std::fmt::Display;
+
+use chrono::{NaiveDate, NaiveTime};
 use log::{debug, error, info, warn};
 use nom::error::{Error, ErrorKind};
 use nom::Err;
 use nom::{
-    branch::alt, bytes::complete::tag, character::complete::alpha1, error::context,
-    number::complete::double, sequence::tuple, IResult,
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::{char, digit0, digit1, one_of, space0, space1},
+    combinator::{consumed, map, opt, recognize},
+    error::context,
+    multi::many0,
+    sequence::{pair, preceded, tuple},
+    IResult,
 };
 
-use crate::converter::{error::ConversionError, UnitConversion};
+use crate::angle::{parse_dms, DMS_TARGET, DMS_UNIT, DMS_UNITS};
+use crate::converter::{error::ConversionError, CaseSensitivity, UnitComponent, UnitConversion};
+use crate::duration::{parse_iso_duration, DURATION_UNIT};
+use crate::precision::significant_figures;
 
 #[derive(Debug, Clone)]
 pub struct UnitAbbreviation {
     pub unit: String,
     pub abbrev: String,
     pub unit_type: String,
+    /// The region of units with regional variants (i.e. "us" or "imp" for gallons).
+    pub region: Option<String>,
+}
+
+/// An abbreviation used by units of several types (i.e. "pt" for both pints and points).
+#[derive(Debug, PartialEq, Clone)]
+pub struct AbbreviationCollision {
+    pub abbrev: String,
+    pub unit_types: Vec<String>,
 }
 
+impl Display for AbbreviationCollision {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "'{}' is used by units of types {}",
+            self.abbrev,
+            self.unit_types.join(", ")
+        )
+    }
+}
+
+/// Parses a conversion expression, resolving abbreviations used by several regional variants of a
+/// unit (i.e. "gal") to the variant for `region`.
 pub fn parse_conversion(
     abbreviations: &Vec<UnitAbbreviation>,
     input: &str,
+    region: &str,
+    case: CaseSensitivity,
 ) -> Result<UnitConversion, ConversionError> {
-    info!("Attempting to parse expression {}", input,);
     debug!(
         "Parse function has abbreviations for {} units: {:?}",
         &abbreviations.len(),
         &abbreviations
     );
 
+    let ParsedExpression {
+        value,
+        from: convert_from,
+        substance,
+        to: convert_to,
+        components,
+        date,
+        uncertainty,
+        significant_figures,
+    } = parse_expression(input)?;
+
+    // Abbreviations used by several unit types resolve to units of the same type where possible
+    // (i.e. "pt" for both pints and points), and are otherwise ambiguous unless qualified
+    // with the unit type (i.e. "Capacity:pt").
+    let from_candidates = unit_candidates(abbreviations, convert_from, case)?;
+    let to_candidates = unit_candidates(abbreviations, convert_to, case)?;
+    let common_type = from_candidates
+        .iter()
+        .map(|from| from.unit_type.as_str())
+        .find(|unit_type| to_candidates.iter().any(|to| to.unit_type == *unit_type));
+    if common_type.is_none() {
+        for (input, candidates) in [
+            (convert_from, &from_candidates),
+            (convert_to, &to_candidates),
+        ] {
+            if let Some(err) = ambiguous_unit_error(input, candidates) {
+                return Err(err);
+            }
+        }
+    }
+    let parsed_convert_from = parse_unit(abbreviations, convert_from, common_type, region, case)?;
+    let parsed_convert_to = parse_unit(
+        abbreviations,
+        convert_to,
+        common_type.or(Some(&parsed_convert_from.unit_type)),
+        region,
+        case,
+    )?;
+    debug!(
+        "Parsed units from {} to {} and {} to {}",
+        convert_from, parsed_convert_from.unit, convert_to, parsed_convert_to.unit
+    );
+    let first_type = parsed_convert_from.unit_type.to_owned();
+    let second_type = parsed_convert_to.unit_type.to_owned();
+
+    // Units of different types can only be converted between using the properties of a substance.
+    if first_type != second_type && substance.is_none() {
+        return Err(ConversionError::new("Units are of different types"));
+    }
+
+    let components = components
+        .iter()
+        .map(|abbrev| parse_unit(abbreviations, abbrev, Some(&second_type), region, case))
+        .collect::<Result<Vec<_>, _>>()?;
+    if components.iter().any(|unit| unit.unit_type != second_type) {
+        return Err(ConversionError::new("Units are of different types"));
+    }
+
+    let mut warnings = vec![];
+    for unit in [parsed_convert_from, parsed_convert_to]
+        .into_iter()
+        .chain(components.iter().copied())
+    {
+        if let Some(warning) = regional_variant_warning(abbreviations, unit) {
+            if !warnings.contains(&warning) {
+                warnings.push(warning);
+            }
+        }
+    }
+    let to_type = (second_type != first_type).then_some(second_type);
+
+    Ok(UnitConversion {
+        value,
+        from: parsed_convert_from.unit.to_owned(),
+        to: parsed_convert_to.unit.to_owned(),
+        unit_type: first_type,
+        to_type,
+        substance: substance.map(|s| s.to_owned()),
+        date,
+        components: components
+            .iter()
+            .map(|unit| UnitComponent {
+                value: 0.0,
+                unit: unit.unit.to_owned(),
+                abbrev: unit.abbrev.to_owned(),
+            })
+            .collect(),
+        warnings,
+        uncertainty,
+        significant_figures,
+        ..Default::default()
+    })
+}
+
+/// The parts of a conversion expression, with the units not yet parsed.
+#[derive(Debug, PartialEq)]
+pub struct ParsedExpression<'a> {
+    pub value: f64,
+    pub from: &'a str,
+    pub substance: Option<&'a str>,
+    pub to: &'a str,
+    /// Every target unit when the result is split across several units (i.e. "-> ft in"),
+    /// starting with the target unit.
+    pub components: Vec<&'a str>,
+    pub date: Option<NaiveDate>,
+    /// The absolute uncertainty of the value, which can be given as a percentage of the value
+    /// (i.e. "12.5 ± 2% cm").
+    pub uncertainty: Option<f64>,
+    /// The number of significant figures of the value as it was written, which is only known
+    /// when the value is given as a number.
+    pub significant_figures: Option<u32>,
+}
+
+/// Splits a conversion expression into the value, the unparsed source and target units, the
+/// optional substance being converted (i.e. "2 cups of flour -> g") and the optional date of the
+/// conversion (i.e. "100 USD -> EUR @ 2024-03-01"). The value and source unit can also be given
+/// as an ISO 8601 duration (i.e. "PT1H30M -> min") or an angle in degrees, minutes and seconds
+/// (i.e. "45°30'15\" -> rad"), and the target as "dms" to split an angle into degrees, minutes
+/// and seconds. Values can have an uncertainty (i.e. "12.5 ± 0.2 cm -> in"), which is in degrees
+/// for angles in degrees, minutes and seconds.
+pub fn parse_expression(input: &str) -> Result<ParsedExpression<'_>, ConversionError> {
+    info!("Attempting to parse expression {}", input,);
+
     let result = context(
         "conversion",
         tuple((
-            parse_number,
-            parse_abbreviation,
+            alt((
+                map(parse_iso_duration, |value| {
+                    (value, None, None, DURATION_UNIT)
+                }),
+                map(
+                    pair(parse_dms, opt(parse_uncertainty)),
+                    |(value, uncertainty)| (value, None, uncertainty, DMS_UNIT),
+                ),
+                map(
+                    tuple((
+                        consumed(parse_number),
+                        opt(parse_uncertainty),
+                        preceded(space0, parse_abbreviation),
+                    )),
+                    |((literal, value), uncertainty, from)| {
+                        (value, Some(literal), uncertainty, from)
+                    },
+                ),
+            )),
+            opt(parse_substance),
             parse_operator,
             parse_abbreviation,
+            many0(preceded(space1, parse_abbreviation)),
+            opt(parse_date),
         )),
     )(input);
 
     match result {
-        Ok((_, (value, convert_from, _, convert_to))) => {
-            let (first_type, parsed_convert_from) = parse_unit(abbreviations, convert_from)?;
-            debug!(
-                "Parsed first unit from {} to {}",
-                convert_from, parsed_convert_from
-            );
-
-            let (second_type, parsed_convert_to) = parse_unit(abbreviations, convert_to)?;
-            debug!(
-                "Parsed second unit from {} to {}",
-                convert_to, parsed_convert_to
-            );
-
-            if first_type != second_type {
-                return Err(ConversionError::new("Units are of different types"));
-            }
-
-            Ok(UnitConversion {
+        Ok((_, ((value, literal, uncertainty, from), substance, _, to, components, date))) => {
+            Ok(ParsedExpression {
                 value,
-                from: parsed_convert_from,
-                to: parsed_convert_to,
-                unit_type: first_type,
+                from,
+                substance,
+                to: if to == DMS_TARGET { DMS_UNITS[0] } else { to },
+                components: match (to, components.is_empty()) {
+                    (DMS_TARGET, true) => DMS_UNITS.to_vec(),
+                    (_, true) => components,
+                    (_, false) => [vec![to], components].concat(),
+                },
+                date: parse_date_value(date)?,
+                uncertainty: uncertainty.map(|(uncertainty, relative)| match relative {
+                    Some(_) => (value * uncertainty / 100.0).abs(),
+                    None => uncertainty.abs(),
+                }),
+                significant_figures: literal.map(significant_figures),
             })
         }
         Err(err) => {
             error!("Error parsing expression {}", input);
             error!("{}", err);
-            return Err(ConversionError::new(construct_error_message(&err).as_str()));
+            Err(ConversionError::new(construct_error_message(&err).as_str()))
         }
     }
 }
 
-fn parse_number(input: &str) -> IResult<&str, f64> {
-    context("value", double)(input)
+/// The parts of a time zone conversion expression, with the time zones not yet resolved.
+#[derive(Debug, PartialEq)]
+pub struct ParsedTimeExpression<'a> {
+    pub time: NaiveTime,
+    pub from: &'a str,
+    pub to: &'a str,
+    pub date: Option<NaiveDate>,
+}
+
+/// Splits a time zone conversion expression into the wall-clock time, the source and target
+/// time zones and the optional date (i.e. "14:30 America/New_York -> Asia/Tokyo @ 2024-03-10").
+/// Returns None if the expression doesn't start with a time.
+pub fn parse_time_expression(
+    input: &str,
+) -> Result<Option<ParsedTimeExpression<'_>>, ConversionError> {
+    let Ok((rest, time)) = parse_time(input) else {
+        return Ok(None);
+    };
+    info!("Attempting to parse time zone expression {}", input);
+
+    let format = match time.len() > 5 {
+        true => "%H:%M:%S",
+        false => "%H:%M",
+    };
+    let time = NaiveTime::parse_from_str(time, format)
+        .map_err(|_| ConversionError::new(&format!("'{}' is not a valid time", time)))?;
+
+    let result = context(
+        "time zone conversion",
+        tuple((
+            preceded(space1, parse_abbreviation),
+            parse_operator,
+            parse_abbreviation,
+            opt(parse_date),
+        )),
+    )(rest);
+
+    match result {
+        Ok((_, (from, _, to, date))) => Ok(Some(ParsedTimeExpression {
+            time,
+            from,
+            to,
+            date: parse_date_value(date)?,
+        })),
+        Err(err) => {
+            error!("Error parsing time zone expression {}", input);
+            error!("{}", err);
+            Err(ConversionError::new(construct_error_message(&err).as_str()))
+        }
+    }
+}
+
+/// The parts of a numeral system conversion expression, where the value is a string of digits
+/// in the source system.
+#[derive(Debug, PartialEq)]
+pub struct ParsedNumeralExpression<'a> {
+    pub value: &'a str,
+    pub from: Option<&'a str>,
+    pub to: &'a str,
+}
+
+/// Splits an expression into a value that isn't parsed as a number, the optional source system
+/// and the target system (i.e. "ff hex -> dec" or "MCMXCIV -> dec"). It's up to the caller to
+/// check whether the systems are numeral systems.
+pub fn parse_numeral_expression(input: &str) -> Option<ParsedNumeralExpression<'_>> {
+    let source = alt((
+        map(
+            tuple((
+                parse_abbreviation,
+                preceded(space1, parse_abbreviation),
+                parse_operator,
+            )),
+            |(value, from, _)| (value, Some(from)),
+        ),
+        map(pair(parse_abbreviation, parse_operator), |(value, _)| {
+            (value, None)
+        }),
+    ));
+
+    match pair(source, parse_abbreviation)(input.trim()) {
+        Ok(("", ((value, from), to))) => Some(ParsedNumeralExpression { value, from, to }),
+        _ => None,
+    }
+}
+
+fn parse_time(input: &str) -> IResult<&str, &str> {
+    recognize(tuple((
+        digit1,
+        tag(":"),
+        digit1,
+        opt(pair(tag(":"), digit1)),
+    )))(input)
+}
+
+fn parse_date_value(date: Option<&str>) -> Result<Option<NaiveDate>, ConversionError> {
+    date.map(|date| {
+        date.parse()
+            .map_err(|_| ConversionError::new(&format!("'{}' is not a valid date", date)))
+    })
+    .transpose()
+}
+
+/// Parses a number, which unlike `double` only treats an 'e' as an exponent if it's followed by
+/// digits (i.e. "2em" is 2 em rather than an invalid exponent).
+pub fn parse_number(input: &str) -> IResult<&str, f64> {
+    match context("value", recognize_number)(input) {
+        Ok((rest, number)) => match number.parse() {
+            Ok(value) => Ok((rest, value)),
+            Err(_) => Err(Err::Error(Error::new(input, ErrorKind::Float))),
+        },
+        Err(_) => Err(Err::Error(Error::new(input, ErrorKind::Float))),
+    }
+}
+
+fn recognize_number(input: &str) -> IResult<&str, &str> {
+    recognize(tuple((
+        opt(one_of("+-")),
+        alt((
+            recognize(pair(digit1, opt(pair(char('.'), digit0)))),
+            recognize(pair(char('.'), digit1)),
+        )),
+        opt(tuple((one_of("eE"), opt(one_of("+-")), digit1))),
+    )))(input)
 }
 
 fn parse_abbreviation(input: &str) -> IResult<&str, &str> {
-    context("unit", alpha1)(input)
+    let end = input
+        .char_indices()
+        .find(|&(pos, c)| c.is_whitespace() || c == '@' || input[pos..].starts_with("->"))
+        .map_or(input.len(), |(pos, _)| pos);
+
+    if end == 0 {
+        return Err(Err::Error(Error::new(input, ErrorKind::Alpha)));
+    }
+    Ok((&input[end..], &input[..end]))
+}
+
+/// Parses the name of a substance following the source unit, which may be preceded by "of" and
+/// may contain spaces (i.e. " of brown sugar").
+fn parse_substance(input: &str) -> IResult<&str, &str> {
+    let (rest, _) = space1(input)?;
+    let end = ["->", " to "]
+        .iter()
+        .filter_map(|operator| input.find(operator))
+        .min()
+        .unwrap_or(input.len());
+
+    let name = rest[..end.saturating_sub(input.len() - rest.len())].trim_end();
+    let name = name.strip_prefix("of ").unwrap_or(name).trim_start();
+    if name.is_empty() {
+        return Err(Err::Error(Error::new(input, ErrorKind::Alpha)));
+    }
+
+    // Leave any whitespace before the operator to be parsed with it.
+    let name_end = input[..end].trim_end().len();
+    Ok((&input[name_end..], name))
+}
+
+/// Parses the date following the target unit (i.e. " @ 2024-03-01").
+fn parse_date(input: &str) -> IResult<&str, &str> {
+    preceded(tuple((space0, tag("@"), space0)), parse_abbreviation)(input)
+}
+
+/// Parses an uncertainty following the value, as an absolute value or a percentage of the value.
+fn parse_uncertainty(input: &str) -> IResult<&str, (f64, Option<char>)> {
+    preceded(
+        tuple((space0, alt((tag("±"), tag("+/-"), tag("+-"))), space0)),
+        pair(parse_number, opt(char('%'))),
+    )(input)
 }
 
 fn parse_operator(input: &str) -> IResult<&str, &str> {
     alt((tag(" -> "), tag("->"), tag(" to ")))(input)
 }
 
-fn parse_unit(
-    units: &Vec<UnitAbbreviation>,
+/// Finds the unit for an abbreviation, preferring a unit of the given type if the abbreviation
+/// is used by several types, and then the variant for the region if the abbreviation is used by
+/// several regional variants of a unit.
+fn parse_unit<'a>(
+    units: &'a [UnitAbbreviation],
     input: &str,
-) -> Result<(String, String), ConversionError> {
-    for unit in units {
-        if unit.abbrev == input {
-            return Ok((unit.unit_type.to_string(), unit.unit.to_string()));
+    preferred_type: Option<&str>,
+    region: &str,
+    case: CaseSensitivity,
+) -> Result<&'a UnitAbbreviation, ConversionError> {
+    let matches = unit_candidates(units, input, case)?;
+    let unit_type = preferred_type
+        .filter(|unit_type| matches.iter().any(|u| u.unit_type == *unit_type))
+        .or(matches.first().map(|u| u.unit_type.as_str()));
+    let candidates: Vec<&UnitAbbreviation> = matches
+        .iter()
+        .copied()
+        .filter(|u| Some(u.unit_type.as_str()) == unit_type)
+        .collect();
+
+    let unit = candidates
+        .iter()
+        .find(|u| u.region.as_deref() == Some(region))
+        .or_else(|| candidates.iter().find(|u| u.region.is_none()))
+        .or(candidates.first());
+    match unit {
+        Some(unit) => Ok(unit),
+        None => {
+            warn!("Error parsing {} into a valid unit", input);
+            Err(ConversionError::new(&format!(
+                "'{}' is not a valid unit",
+                input
+            )))
+        }
+    }
+}
+
+/// Finds the unit for an abbreviation, returning the first unit if it's used by several types.
+pub fn find_unit<'a>(
+    units: &'a [UnitAbbreviation],
+    input: &str,
+    case: CaseSensitivity,
+) -> Result<&'a UnitAbbreviation, ConversionError> {
+    unit_candidates(units, input, case)?
+        .first()
+        .copied()
+        .ok_or_else(|| ConversionError::new(&format!("'{}' is not a valid unit", input)))
+}
+
+/// Finds every unit an abbreviation could refer to, where the abbreviation can be qualified with
+/// the unit type to only match units of that type (i.e. "Capacity:ml"), with underscores for the
+/// spaces in its name (i.e. "Data_Rate:MB/s"). Abbreviations that only
+/// differ by case (i.e. "Mm" and "mm") are ambiguous when case is ignored.
+fn unit_candidates<'a>(
+    units: &'a [UnitAbbreviation],
+    input: &str,
+    case: CaseSensitivity,
+) -> Result<Vec<&'a UnitAbbreviation>, ConversionError> {
+    let (unit_type, abbrev) = match input.split_once(':') {
+        Some((unit_type, abbrev))
+            if units
+                .iter()
+                .any(|u| case.matches(&type_qualifier(&u.unit_type), unit_type)) =>
+        {
+            (Some(unit_type), abbrev)
+        }
+        _ => (None, input),
+    };
+    let of_type = |u: &&UnitAbbreviation| {
+        unit_type.is_none_or(|t| case.matches(&type_qualifier(&u.unit_type), t))
+    };
+
+    let exact: Vec<&UnitAbbreviation> = units
+        .iter()
+        .filter(|u| u.abbrev == abbrev)
+        .filter(of_type)
+        .collect();
+    if case == CaseSensitivity::Exact
+        || (case == CaseSensitivity::InsensitiveWhenUnambiguous && !exact.is_empty())
+    {
+        return Ok(exact);
+    }
+
+    let folded: Vec<&UnitAbbreviation> = units
+        .iter()
+        .filter(|u| u.abbrev.to_lowercase() == abbrev.to_lowercase())
+        .filter(of_type)
+        .collect();
+    let mut abbrevs: Vec<&UnitAbbreviation> = vec![];
+    for unit in &folded {
+        if !abbrevs.iter().any(|a| a.abbrev == unit.abbrev) {
+            abbrevs.push(unit);
+        }
+    }
+    if abbrevs.len() > 1 {
+        let abbrevs: Vec<String> = abbrevs
+            .iter()
+            .map(|a| format!("{} ({})", a.abbrev, a.unit))
+            .collect();
+        return Err(ConversionError::new(&format!(
+            "'{}' is ambiguous when ignoring case, use one of {}",
+            input,
+            abbrevs.join(", ")
+        )));
+    }
+    Ok(folded)
+}
+
+/// Writes a unit type as it's used to qualify an abbreviation, which can't contain spaces (i.e.
+/// "Data_Rate" for "Data Rate").
+fn type_qualifier(unit_type: &str) -> String {
+    unit_type.replace(' ', "_")
+}
+
+/// Returns an error listing every unit an abbreviation could refer to if it's used by different
+/// units of several types. The same unit repeated in several types (i.e. millimeters in Length
+/// and Typography) isn't ambiguous.
+fn ambiguous_unit_error(input: &str, candidates: &[&UnitAbbreviation]) -> Option<ConversionError> {
+    if candidates.iter().all(|c| c.unit == candidates[0].unit) {
+        return None;
+    }
+    let mut unit_types: Vec<(&str, Vec<&str>)> = vec![];
+    for candidate in candidates {
+        match unit_types
+            .iter_mut()
+            .find(|(unit_type, _)| *unit_type == candidate.unit_type)
+        {
+            Some((_, units)) if !units.contains(&candidate.unit.as_str()) => {
+                units.push(&candidate.unit)
+            }
+            Some(_) => {}
+            None => unit_types.push((&candidate.unit_type, vec![&candidate.unit])),
         }
     }
-    warn!("Error parsing {} into a valid unit", input);
-    Err(ConversionError::new(&format!(
-        "'{}' is not a valid unit",
-        input
+    if unit_types.len() < 2 {
+        return None;
+    }
+
+    let candidates: Vec<String> = unit_types
+        .iter()
+        .map(|(unit_type, units)| {
+            format!(
+                "{}:{} ({})",
+                type_qualifier(unit_type),
+                input,
+                units.join(" or ")
+            )
+        })
+        .collect();
+    Some(ConversionError::new(&format!(
+        "'{}' is ambiguous, use one of {}",
+        input,
+        candidates.join(", ")
     )))
 }
 
+/// Finds abbreviations used by different units of several types, which are resolved using the
+/// other unit in a conversion or need to be qualified with the unit type.
+pub fn find_collisions(units: &[UnitAbbreviation]) -> Vec<AbbreviationCollision> {
+    let mut result: Vec<AbbreviationCollision> = vec![];
+    for unit in units {
+        if result.iter().any(|c| c.abbrev == unit.abbrev)
+            || units
+                .iter()
+                .filter(|u| u.abbrev == unit.abbrev)
+                .all(|u| u.unit == unit.unit)
+        {
+            continue;
+        }
+        let mut unit_types: Vec<String> = vec![];
+        for other in units.iter().filter(|u| u.abbrev == unit.abbrev) {
+            if !unit_types.contains(&other.unit_type) {
+                unit_types.push(other.unit_type.to_owned());
+            }
+        }
+        if unit_types.len() > 1 {
+            result.push(AbbreviationCollision {
+                abbrev: unit.abbrev.to_owned(),
+                unit_types,
+            });
+        }
+    }
+    result
+}
+
+/// Returns a warning if a regional variant of a unit was used for an abbreviation shared with
+/// other variants, listing the qualified abbreviations of the other variants.
+fn regional_variant_warning(units: &[UnitAbbreviation], unit: &UnitAbbreviation) -> Option<String> {
+    let region = unit.region.as_ref()?;
+    let others: Vec<String> = units
+        .iter()
+        .filter(|u| u.abbrev == unit.abbrev && u.unit_type == unit.unit_type && u.unit != unit.unit)
+        .map(|u| match &u.region {
+            Some(other) => format!("{} ({}_{})", u.unit, u.abbrev, other),
+            None => u.unit.to_owned(),
+        })
+        .collect();
+    if others.is_empty() {
+        return None;
+    }
+
+    Some(format!(
+        "'{}' is ambiguous, using {} for region {} (could also be {})",
+        unit.abbrev,
+        unit.unit,
+        region,
+        others.join(", ")
+    ))
+}
+
 fn construct_error_message(err: &Err<Error<&str>>) -> String {
     fn format_error_message(code: ErrorKind) -> String {
         match code {
@@ -124,38 +654,56 @@ mod tests {
                 unit: "Celsius".to_string(),
                 abbrev: "C".to_string(),
                 unit_type: "Temperature".to_string(),
+                region: None,
             },
             UnitAbbreviation {
                 unit: "Fahrenheit".to_string(),
                 abbrev: "F".to_string(),
                 unit_type: "Temperature".to_string(),
+                region: None,
             },
             UnitAbbreviation {
                 unit: String::from("Millimeter"),
                 abbrev: String::from("mm"),
                 unit_type: String::from("Length"),
+                region: None,
             },
             UnitAbbreviation {
                 unit: String::from("Megameter"),
                 abbrev: String::from("Mm"),
                 unit_type: String::from("Length"),
+                region: None,
             },
             UnitAbbreviation {
                 unit: String::from("Kilometer"),
                 abbrev: String::from("km"),
                 unit_type: String::from("Length"),
+                region: None,
+            },
+            UnitAbbreviation {
+                unit: String::from("Gram"),
+                abbrev: String::from("g"),
+                unit_type: String::from("Weight"),
+                region: None,
+            },
+            UnitAbbreviation {
+                unit: String::from("Cup"),
+                abbrev: String::from("cups"),
+                unit_type: String::from("Capacity"),
+                region: None,
             },
             UnitAbbreviation {
                 unit: String::from("NauticalMile"),
                 abbrev: String::from("nmi"),
                 unit_type: String::from("Length"),
+                region: None,
             },
         ]
     }
 
     #[test]
     fn valid_temperature_conversion() {
-        let mut input = "20C -> F";
+        let input = "20C -> F";
         let abbreviations = construct_unit_abbreviations();
 
         let expected = UnitConversion {
@@ -163,32 +711,34 @@ mod tests {
             from: "Celsius".to_string(),
             to: "Fahrenheit".to_string(),
             unit_type: "Temperature".to_string(),
+            significant_figures: Some(1),
+            ..Default::default()
         };
-        let actual = parse_conversion(&abbreviations, &mut input).unwrap();
+        let actual = parse_conversion(&abbreviations, input, "us", CaseSensitivity::Exact).unwrap();
         assert_eq!(expected, actual);
     }
 
     #[test]
     fn invalid_unit() {
-        let mut input = "20x -> F";
+        let input = "20x -> F";
         let abbreviations = construct_unit_abbreviations();
 
-        let actual = parse_conversion(&abbreviations, &mut input);
+        let actual = parse_conversion(&abbreviations, input, "us", CaseSensitivity::Exact);
         assert!(actual.is_err());
     }
 
     #[test]
     fn invalid_unit_value() {
-        let mut input = "C -> F";
+        let input = "C -> F";
         let abbreviations = construct_unit_abbreviations();
 
-        let actual = parse_conversion(&abbreviations, &mut input);
+        let actual = parse_conversion(&abbreviations, input, "us", CaseSensitivity::Exact);
         assert!(actual.is_err());
     }
 
     #[test]
     fn same_characters_different_case() {
-        let mut input = "1Mm -> mm";
+        let input = "1Mm -> mm";
         let abbreviations = construct_unit_abbreviations();
 
         let expected = UnitConversion {
@@ -196,14 +746,16 @@ mod tests {
             from: String::from("Megameter"),
             to: String::from("Millimeter"),
             unit_type: String::from("Length"),
+            significant_figures: Some(1),
+            ..Default::default()
         };
-        let actual = parse_conversion(&abbreviations, &mut input).unwrap();
+        let actual = parse_conversion(&abbreviations, input, "us", CaseSensitivity::Exact).unwrap();
         assert_eq!(expected, actual)
     }
 
     #[test]
     fn e_notation() {
-        let mut input = "1.079913e9km -> nmi";
+        let input = "1.079913e9km -> nmi";
         let abbreviations = construct_unit_abbreviations();
 
         let expected = UnitConversion {
@@ -211,8 +763,431 @@ mod tests {
             from: String::from("Kilometer"),
             to: String::from("NauticalMile"),
             unit_type: String::from("Length"),
+            significant_figures: Some(7),
+            ..Default::default()
         };
-        let actual = parse_conversion(&abbreviations, &mut input).unwrap();
+        let actual = parse_conversion(&abbreviations, input, "us", CaseSensitivity::Exact).unwrap();
         assert_eq!(expected, actual)
     }
+
+    #[test]
+    fn whitespace_between_value_and_unit() {
+        let input = "20 C -> F";
+        let abbreviations = construct_unit_abbreviations();
+
+        let actual = parse_conversion(&abbreviations, input, "us", CaseSensitivity::Exact).unwrap();
+        assert_eq!(20.0, actual.value);
+        assert_eq!("Celsius", actual.from);
+    }
+
+    #[test]
+    fn substance() {
+        for (input, substance) in [
+            ("2 cups of flour -> g", "flour"),
+            ("2cups brown sugar -> g", "brown sugar"),
+            ("2 cups of  olive oil->g", "olive oil"),
+            ("2 cups water to g", "water"),
+        ] {
+            let actual = parse_expression(input).unwrap();
+            let expected = ParsedExpression {
+                value: 2.0,
+                from: "cups",
+                substance: Some(substance),
+                to: "g",
+                components: vec![],
+                date: None,
+                uncertainty: None,
+                significant_figures: Some(1),
+            };
+            assert_eq!(expected, actual, "{}", input);
+        }
+    }
+
+    #[test]
+    fn substance_between_types() {
+        let abbreviations = construct_unit_abbreviations();
+        let actual = parse_conversion(
+            &abbreviations,
+            "2 cups of flour -> g",
+            "us",
+            CaseSensitivity::Exact,
+        )
+        .unwrap();
+
+        let expected = UnitConversion {
+            value: 2.0,
+            from: String::from("Cup"),
+            to: String::from("Gram"),
+            unit_type: String::from("Capacity"),
+            to_type: Some(String::from("Weight")),
+            substance: Some(String::from("flour")),
+            significant_figures: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn different_types_without_substance() {
+        let abbreviations = construct_unit_abbreviations();
+        let actual = parse_conversion(&abbreviations, "2 cups -> g", "us", CaseSensitivity::Exact);
+        assert!(actual.is_err());
+    }
+
+    #[test]
+    fn no_substance() {
+        for input in ["1km to mm", "1 km -> mm", "1km->mm"] {
+            let actual = parse_expression(input).unwrap();
+            assert_eq!(None, actual.substance, "{}", input);
+            assert_eq!("mm", actual.to);
+        }
+    }
+
+    #[test]
+    fn date() {
+        for input in [
+            "100 USD -> EUR @ 2024-03-01",
+            "100 USD -> EUR@2024-03-01",
+            "100USD to EUR @2024-03-01",
+        ] {
+            let actual = parse_expression(input).unwrap();
+            assert_eq!("EUR", actual.to, "{}", input);
+            assert_eq!(
+                NaiveDate::from_ymd_opt(2024, 3, 1),
+                actual.date,
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_date() {
+        let actual = parse_expression("100 USD -> EUR @ 2024-02-30");
+        assert_eq!(
+            "'2024-02-30' is not a valid date",
+            actual.unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn iso_duration() {
+        let actual = parse_expression("PT1H30M -> min").unwrap();
+        assert_eq!(5400.0, actual.value);
+        assert_eq!("s", actual.from);
+        assert_eq!("min", actual.to);
+    }
+
+    #[test]
+    fn components() {
+        for input in ["90061 s -> d h min s", "90061s->d h min s @ 2024-03-01"] {
+            let actual = parse_expression(input).unwrap();
+            assert_eq!("d", actual.to, "{}", input);
+            assert_eq!(vec!["d", "h", "min", "s"], actual.components, "{}", input);
+        }
+    }
+
+    #[test]
+    fn time_expression() {
+        let actual = parse_time_expression("14:30 America/New_York -> Asia/Tokyo @ 2024-03-10")
+            .unwrap()
+            .unwrap();
+        let expected = ParsedTimeExpression {
+            time: NaiveTime::from_hms_opt(14, 30, 0).unwrap(),
+            from: "America/New_York",
+            to: "Asia/Tokyo",
+            date: NaiveDate::from_ymd_opt(2024, 3, 10),
+        };
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn not_time_expression() {
+        assert_eq!(None, parse_time_expression("14 km -> m").unwrap());
+    }
+
+    #[test]
+    fn invalid_time() {
+        let actual = parse_time_expression("25:30 UTC -> CET");
+        assert_eq!(
+            "'25:30' is not a valid time",
+            actual.unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn numeral_expression() {
+        for (input, value, from, to) in [
+            ("255 dec -> hex", "255", Some("dec"), "hex"),
+            ("0b1011 -> dec", "0b1011", None, "dec"),
+            ("MCMXCIV to dec", "MCMXCIV", None, "dec"),
+        ] {
+            let expected = ParsedNumeralExpression { value, from, to };
+            assert_eq!(Some(expected), parse_numeral_expression(input), "{}", input);
+        }
+        assert_eq!(None, parse_numeral_expression("2 cups of flour -> g"));
+    }
+
+    #[test]
+    fn dms() {
+        let actual = parse_expression("45 30 15 N -> dms").unwrap();
+        assert_eq!(45.50416666666667, actual.value);
+        assert_eq!("deg", actual.from);
+        assert_eq!("deg", actual.to);
+        assert_eq!(vec!["deg", "arcmin", "arcsec"], actual.components);
+
+        let actual = parse_expression("45°30'15\" ± 0.1 -> dms").unwrap();
+        assert_eq!(45.50416666666667, actual.value);
+        assert_eq!(Some(0.1), actual.uncertainty);
+        assert_eq!(None, actual.substance);
+    }
+
+    #[test]
+    fn number_followed_by_e() {
+        for (input, value, to) in [
+            ("2em -> px", 2.0, "px"),
+            ("1.5e3 m -> km", 1500.0, "km"),
+            ("2E-3km->m", 0.002, "m"),
+            (".5 rem -> px", 0.5, "px"),
+        ] {
+            let actual = parse_expression(input).unwrap();
+            assert_eq!(value, actual.value, "{}", input);
+            assert_eq!(to, actual.to, "{}", input);
+        }
+    }
+
+    #[test]
+    fn abbreviation_used_by_several_types() {
+        let mut abbreviations = construct_unit_abbreviations();
+        abbreviations.push(UnitAbbreviation {
+            unit: "Grains".to_string(),
+            abbrev: "C".to_string(),
+            unit_type: "Weight".to_string(),
+            region: None,
+        });
+
+        let actual =
+            parse_conversion(&abbreviations, "1 g -> C", "us", CaseSensitivity::Exact).unwrap();
+        assert_eq!("Grains", actual.to);
+        let actual =
+            parse_conversion(&abbreviations, "1 C -> g", "us", CaseSensitivity::Exact).unwrap();
+        assert_eq!("Grains", actual.from);
+        let actual =
+            parse_conversion(&abbreviations, "1 C -> F", "us", CaseSensitivity::Exact).unwrap();
+        assert_eq!("Celsius", actual.from);
+    }
+
+    #[test]
+    fn regional_variants() {
+        let mut abbreviations = construct_unit_abbreviations();
+        for (unit, abbrev, region) in [
+            ("USGallons", "gal", "us"),
+            ("USGallons", "gal_us", "us"),
+            ("ImperialGallons", "gal", "imp"),
+            ("ImperialGallons", "gal_imp", "imp"),
+            ("Liters", "l", ""),
+        ] {
+            abbreviations.push(UnitAbbreviation {
+                unit: unit.to_string(),
+                abbrev: abbrev.to_string(),
+                unit_type: "Capacity".to_string(),
+                region: (!region.is_empty()).then(|| region.to_string()),
+            });
+        }
+
+        let actual =
+            parse_conversion(&abbreviations, "1 gal -> l", "imp", CaseSensitivity::Exact).unwrap();
+        assert_eq!("ImperialGallons", actual.from);
+        assert_eq!(
+            vec!["'gal' is ambiguous, using ImperialGallons for region imp (could also be USGallons (gal_us))"],
+            actual.warnings
+        );
+
+        let actual = parse_conversion(
+            &abbreviations,
+            "1 gal_us -> l",
+            "imp",
+            CaseSensitivity::Exact,
+        )
+        .unwrap();
+        assert_eq!("USGallons", actual.from);
+        assert!(actual.warnings.is_empty());
+    }
+
+    #[test]
+    fn qualified_abbreviation() {
+        let mut abbreviations = construct_unit_abbreviations();
+        abbreviations.push(UnitAbbreviation {
+            unit: "Grains".to_string(),
+            abbrev: "C".to_string(),
+            unit_type: "Weight".to_string(),
+            region: None,
+        });
+
+        let actual = parse_conversion(
+            &abbreviations,
+            "1 Weight:C -> g",
+            "us",
+            CaseSensitivity::Exact,
+        )
+        .unwrap();
+        assert_eq!("Grains", actual.from);
+        let actual = parse_conversion(
+            &abbreviations,
+            "1 Temperature:C -> F",
+            "us",
+            CaseSensitivity::Exact,
+        )
+        .unwrap();
+        assert_eq!("Celsius", actual.from);
+        let actual = parse_conversion(
+            &abbreviations,
+            "1 Length:C -> F",
+            "us",
+            CaseSensitivity::Exact,
+        );
+        assert!(actual.is_err());
+    }
+
+    #[test]
+    fn ambiguous_abbreviation() {
+        let mut abbreviations = construct_unit_abbreviations();
+        abbreviations.push(UnitAbbreviation {
+            unit: "Grains".to_string(),
+            abbrev: "C".to_string(),
+            unit_type: "Weight".to_string(),
+            region: None,
+        });
+
+        let actual = parse_conversion(&abbreviations, "1 C -> km", "us", CaseSensitivity::Exact);
+        assert_eq!(
+            "'C' is ambiguous, use one of Temperature:C (Celsius), Weight:C (Grains)",
+            actual.unwrap_err().to_string()
+        );
+
+        // Spaces in the unit type are written as underscores, which can be used to qualify it.
+        abbreviations.push(UnitAbbreviation {
+            unit: "Cycles".to_string(),
+            abbrev: "C".to_string(),
+            unit_type: "Data Rate".to_string(),
+            region: None,
+        });
+        let actual = parse_conversion(&abbreviations, "1 C -> km", "us", CaseSensitivity::Exact);
+        assert_eq!(
+            "'C' is ambiguous, use one of Temperature:C (Celsius), Weight:C (Grains), Data_Rate:C (Cycles)",
+            actual.unwrap_err().to_string()
+        );
+        let actual = parse_conversion(
+            &abbreviations,
+            "1 Data_Rate:C -> C",
+            "us",
+            CaseSensitivity::Exact,
+        );
+        assert_eq!("Cycles", actual.unwrap().from);
+    }
+
+    #[test]
+    fn collisions() {
+        let mut abbreviations = construct_unit_abbreviations();
+        abbreviations.push(UnitAbbreviation {
+            unit: "Grains".to_string(),
+            abbrev: "C".to_string(),
+            unit_type: "Weight".to_string(),
+            region: None,
+        });
+
+        abbreviations.push(UnitAbbreviation {
+            unit: "Kilometer".to_string(),
+            abbrev: "km".to_string(),
+            unit_type: "Typography".to_string(),
+            region: None,
+        });
+
+        let actual = find_collisions(&abbreviations);
+        assert_eq!(
+            vec![AbbreviationCollision {
+                abbrev: "C".to_string(),
+                unit_types: vec!["Temperature".to_string(), "Weight".to_string()],
+            }],
+            actual
+        );
+    }
+
+    #[test]
+    fn case_sensitivity() {
+        let abbreviations = construct_unit_abbreviations();
+
+        let actual = parse_conversion(&abbreviations, "1 KM -> mm", "us", CaseSensitivity::Exact);
+        assert!(actual.is_err());
+        let actual = parse_conversion(
+            &abbreviations,
+            "1 KM -> mm",
+            "us",
+            CaseSensitivity::InsensitiveWhenUnambiguous,
+        )
+        .unwrap();
+        assert_eq!("Kilometer", actual.from);
+        assert_eq!("Millimeter", actual.to);
+        let actual = parse_conversion(
+            &abbreviations,
+            "1 km -> Mm",
+            "us",
+            CaseSensitivity::InsensitiveWhenUnambiguous,
+        )
+        .unwrap();
+        assert_eq!("Megameter", actual.to);
+    }
+
+    #[test]
+    fn case_insensitive_ambiguous() {
+        let abbreviations = construct_unit_abbreviations();
+
+        for case in [
+            CaseSensitivity::InsensitiveWhenUnambiguous,
+            CaseSensitivity::Insensitive,
+        ] {
+            let actual = parse_conversion(&abbreviations, "1 km -> MM", "us", case);
+            assert_eq!(
+                "'MM' is ambiguous when ignoring case, use one of mm (Millimeter), Mm (Megameter)",
+                actual.unwrap_err().to_string()
+            );
+        }
+        let actual = parse_conversion(
+            &abbreviations,
+            "1 km -> mm",
+            "us",
+            CaseSensitivity::Insensitive,
+        );
+        assert!(actual.is_err());
+    }
+
+    #[test]
+    fn uncertainty() {
+        for (input, value, uncertainty) in [
+            ("12.5 ± 0.2 cm -> in", 12.5, 0.2),
+            ("12.5±0.2cm -> in", 12.5, 0.2),
+            ("12.5 +/- 0.2 cm -> in", 12.5, 0.2),
+            ("-20 ± 5% C -> F", -20.0, 1.0),
+        ] {
+            let actual = parse_expression(input).unwrap();
+            assert_eq!(value, actual.value, "{}", input);
+            assert_eq!(Some(uncertainty), actual.uncertainty, "{}", input);
+        }
+        assert_eq!(None, parse_expression("12.5 cm -> in").unwrap().uncertainty);
+    }
+
+    #[test]
+    fn significant_figures() {
+        for (input, figures) in [
+            ("2 km -> nmi", Some(1)),
+            ("2000 m -> km", Some(1)),
+            ("2.000 in -> m", Some(4)),
+            ("1.50e3 m -> km", Some(3)),
+            ("12.5 ± 0.2 cm -> in", Some(3)),
+            ("PT1H30M -> min", None),
+        ] {
+            let actual = parse_expression(input).unwrap();
+            assert_eq!(figures, actual.significant_figures, "{}", input);
+        }
+    }
 }
@@ -0,0 +1,126 @@
+use crate::rational::Rational;
+
+/// How precisely the multiplier of a conversion is known.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Precision {
+    /// The multiplier is defined exactly, and stored as a fraction when it can be represented as
+    /// one.
+    Exact(Option<Rational>),
+    /// The multiplier has been rounded, so results using it are limited to its number of
+    /// significant figures (i.e. 6 for 39.3701 inches per meter).
+    Approximate(u32),
+    /// Several rounded multipliers combined into one (i.e. a cached path), which is limited to
+    /// the fewest significant figures among them and has the sum of their relative errors.
+    Combined(u32, f64),
+}
+
+impl Precision {
+    pub fn exact(multiplier: f64) -> Precision {
+        Precision::Exact(Rational::from_f64(multiplier))
+    }
+
+    pub fn approximate(multiplier: f64) -> Precision {
+        Precision::Approximate(significant_figures(&multiplier.to_string()))
+    }
+
+    /// The precision of the inverse of the multiplier.
+    pub fn recip(self) -> Precision {
+        match self {
+            Precision::Exact(exact) => Precision::Exact(exact.and_then(|x| x.recip())),
+            Precision::Approximate(figures) => Precision::Approximate(figures),
+            Precision::Combined(figures, error) => Precision::Combined(figures, error),
+        }
+    }
+
+    pub fn rational(self) -> Option<Rational> {
+        match self {
+            Precision::Exact(exact) => exact,
+            Precision::Approximate(_) | Precision::Combined(..) => None,
+        }
+    }
+
+    pub fn significant_figures(self) -> Option<u32> {
+        match self {
+            Precision::Exact(_) => None,
+            Precision::Approximate(figures) | Precision::Combined(figures, _) => Some(figures),
+        }
+    }
+
+    /// The largest relative error of the multiplier, which is half a unit in its last
+    /// significant figure relative to a leading digit of 1 (i.e. 5e-6 for 6 significant figures).
+    pub fn relative_error(self) -> f64 {
+        match self {
+            Precision::Exact(_) => 0.0,
+            Precision::Approximate(figures) => 0.5 * 10f64.powi(1 - figures as i32),
+            Precision::Combined(_, error) => error,
+        }
+    }
+}
+
+/// Counts the significant figures of a number as it was written. Leading zeros aren't
+/// significant, and neither are trailing zeros unless the number has a decimal point (i.e. 1 for
+/// "2000" but 4 for "2000." and "2.000").
+pub fn significant_figures(literal: &str) -> u32 {
+    let mantissa = literal
+        .split(['e', 'E'])
+        .next()
+        .unwrap_or_default()
+        .trim_start_matches(['+', '-']);
+    let digits = mantissa
+        .chars()
+        .filter(|c| c.is_ascii_digit())
+        .collect::<String>();
+    let significant = match mantissa.contains('.') {
+        true => digits.trim_start_matches('0'),
+        false => digits.trim_matches('0'),
+    };
+    (significant.len() as u32).max(1)
+}
+
+/// Rounds a value to a number of significant figures, returning the float closest to the
+/// rounded decimal.
+pub fn round_to_significant_figures(value: f64, figures: u32) -> f64 {
+    if figures == 0 || value == 0.0 || !value.is_finite() {
+        return value;
+    }
+    format!("{:.*e}", figures as usize - 1, value)
+        .parse()
+        .unwrap_or(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn count_significant_figures() {
+        assert_eq!(1, significant_figures("2"));
+        assert_eq!(1, significant_figures("2000"));
+        assert_eq!(4, significant_figures("2000."));
+        assert_eq!(4, significant_figures("2.000"));
+        assert_eq!(2, significant_figures("-0.0050"));
+        assert_eq!(3, significant_figures("1.50e-3"));
+        assert_eq!(6, significant_figures("39.3701"));
+        assert_eq!(1, significant_figures("0"));
+    }
+
+    #[test]
+    fn round() {
+        assert_eq!(1.0, round_to_significant_figures(1.0799136069114472, 1));
+        assert_eq!(1.08, round_to_significant_figures(1.0799136069114472, 3));
+        assert_eq!(0.0508, round_to_significant_figures(0.0508, 4));
+        assert_eq!(-1200.0, round_to_significant_figures(-1234.5, 2));
+        assert_eq!(0.0, round_to_significant_figures(0.0, 3));
+    }
+
+    #[test]
+    fn approximate_precision() {
+        let precision = Precision::approximate(39.3701);
+        assert_eq!(Some(6), precision.significant_figures());
+        assert_eq!(precision, precision.recip());
+        assert_eq!(None, precision.rational());
+        assert_eq!(None, Precision::exact(25.4).significant_figures());
+        assert!((precision.relative_error() - 5e-6).abs() < 1e-18);
+        assert_eq!(0.0, Precision::exact(25.4).relative_error());
+    }
+}
@@ -0,0 +1,97 @@
+/// A fraction used to combine the multipliers along a conversion path without rounding errors.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Rational {
+    numer: i128,
+    denom: i128,
+}
+
+impl Rational {
+    pub const ONE: Rational = Rational { numer: 1, denom: 1 };
+
+    /// Creates a fraction in its lowest terms, returning `None` if the denominator is zero.
+    pub fn new(numer: i128, denom: i128) -> Option<Rational> {
+        if denom == 0 {
+            return None;
+        }
+        let divisor = gcd(numer, denom) * denom.signum();
+        Some(Rational {
+            numer: numer / divisor,
+            denom: denom / divisor,
+        })
+    }
+
+    /// Converts a float to the decimal fraction it was written as (i.e. 0.1 to 1/10 rather than
+    /// the nearest binary fraction), using the shortest representation that round trips.
+    pub fn from_f64(value: f64) -> Option<Rational> {
+        if !value.is_finite() {
+            return None;
+        }
+        let decimal = value.to_string();
+        let (whole, fraction) = decimal.split_once('.').unwrap_or((&decimal, ""));
+        let numer = format!("{}{}", whole, fraction).parse::<i128>().ok()?;
+        let denom = 10i128.checked_pow(fraction.len() as u32)?;
+        Rational::new(numer, denom)
+    }
+
+    pub fn recip(self) -> Option<Rational> {
+        Rational::new(self.denom, self.numer)
+    }
+
+    /// Multiplies two fractions, returning `None` if the result can't be represented.
+    pub fn checked_mul(self, other: Rational) -> Option<Rational> {
+        // Cancelling common factors first keeps the intermediate values as small as possible.
+        let a = gcd(self.numer, other.denom);
+        let b = gcd(other.numer, self.denom);
+        let numer = (self.numer / a).checked_mul(other.numer / b)?;
+        let denom = (self.denom / b).checked_mul(other.denom / a)?;
+        Rational::new(numer, denom)
+    }
+
+    pub fn to_f64(self) -> f64 {
+        self.numer as f64 / self.denom as f64
+    }
+}
+
+fn gcd(a: i128, b: i128) -> i128 {
+    let (mut a, mut b) = (a.abs(), b.abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a.max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_f64() {
+        assert_eq!(Rational::new(1, 10), Rational::from_f64(0.1));
+        assert_eq!(Rational::new(-254, 10), Rational::from_f64(-25.4));
+        assert_eq!(Rational::new(1000, 1), Rational::from_f64(1000.0));
+        assert_eq!(None, Rational::from_f64(f64::NAN));
+        assert_eq!(None, Rational::from_f64(1e300));
+    }
+
+    #[test]
+    fn multiply() {
+        let tenth = Rational::from_f64(0.1).unwrap();
+        let actual = tenth
+            .checked_mul(tenth)
+            .unwrap()
+            .checked_mul(tenth)
+            .unwrap();
+        assert_eq!(0.001, actual.to_f64());
+        assert_eq!(0.1 * 0.1 * 0.1, 0.0010000000000000002);
+
+        let inch = Rational::from_f64(25.4).unwrap();
+        assert_eq!(Some(Rational::ONE), inch.checked_mul(inch.recip().unwrap()));
+    }
+
+    #[test]
+    fn overflow() {
+        let large = Rational::new(i128::MAX, 1).unwrap();
+        assert_eq!(None, large.checked_mul(Rational::new(2, 1).unwrap()));
+        assert_eq!(None, Rational::new(1, 0));
+    }
+}
@@ -0,0 +1,113 @@
+use std::io::Read;
+
+use log::{info, trace};
+
+use crate::converter::error::ConversionError;
+use crate::currency::ExchangeRate;
+use crate::source::input::SourceInput;
+
+/// Loads exchange rates from lines of the form `date,base,currency,rate`, i.e.
+/// `2024-03-01,EUR,USD,1.0842`. A header line starting with "date", blank lines and lines
+/// starting with '#' are ignored.
+pub struct ExchangeRateSourceCsv {
+    input: SourceInput,
+}
+
+impl ExchangeRateSourceCsv {
+    pub fn new(path: &str) -> ExchangeRateSourceCsv {
+        ExchangeRateSourceCsv {
+            input: SourceInput::path(path),
+        }
+    }
+
+    pub fn from_contents(name: &str, contents: &str) -> ExchangeRateSourceCsv {
+        ExchangeRateSourceCsv {
+            input: SourceInput::contents(name, contents),
+        }
+    }
+
+    pub fn from_reader(
+        name: &str,
+        reader: impl Read,
+    ) -> Result<ExchangeRateSourceCsv, ConversionError> {
+        Ok(ExchangeRateSourceCsv {
+            input: SourceInput::reader(name, reader)?,
+        })
+    }
+
+    pub fn load(&self) -> Result<Vec<ExchangeRate>, ConversionError> {
+        let result = self.input.parse(parse_rates)?;
+
+        info!(
+            "Imported {} exchange rates from {}",
+            result.len(),
+            self.input.name()
+        );
+        Ok(result)
+    }
+}
+
+fn parse_rates(contents: &str) -> Result<Vec<ExchangeRate>, ConversionError> {
+    let mut result = vec![];
+    for (number, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with("date") {
+            continue;
+        }
+        trace!("Importing exchange rate {}", line);
+
+        let error =
+            |message: &str| ConversionError::new(&format!("Line {}: {}", number + 1, message));
+        let fields: Vec<&str> = line.split(',').map(|field| field.trim()).collect();
+        let [date, base, currency, rate] = fields[..] else {
+            return Err(error("Expected 4 fields (date,base,currency,rate)"));
+        };
+
+        result.push(ExchangeRate {
+            date: date
+                .parse()
+                .map_err(|_| error(&format!("'{}' is not a valid date", date)))?,
+            base: base.to_owned(),
+            currency: currency.to_owned(),
+            rate: rate
+                .parse()
+                .map_err(|_| error(&format!("'{}' is not a valid rate", rate)))?,
+        });
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    #[test]
+    fn load_rates() {
+        let contents = "date,base,currency,rate\n# Comment\n2024-03-01,EUR,USD,1.0842\n\n2024-03-01, EUR, GBP, 0.8556\n";
+        let actual = ExchangeRateSourceCsv::from_contents("rates", contents)
+            .load()
+            .unwrap();
+
+        let expected = ExchangeRate {
+            date: NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+            base: String::from("EUR"),
+            currency: String::from("GBP"),
+            rate: 0.8556,
+        };
+        assert_eq!(2, actual.len());
+        assert_eq!(expected, actual[1]);
+    }
+
+    #[test]
+    fn invalid_line() {
+        let contents = "2024-03-01,EUR,USD,1.0842\n2024-03-01,EUR,GBP\n";
+        let actual = ExchangeRateSourceCsv::from_contents("rates", contents).load();
+        assert_eq!(
+            "Error loading definitions from rates: Line 2: Expected 4 fields (date,base,currency,rate)",
+            actual.unwrap_err().to_string()
+        );
+    }
+}
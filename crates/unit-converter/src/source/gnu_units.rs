@@ -0,0 +1,620 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
+use std::io::Read;
+
+use log::{debug, info, trace, warn};
+
+use crate::converter::error::ConversionError;
+use crate::parser::UnitAbbreviation;
+use crate::source::input::SourceInput;
+use crate::ConversionDefinition;
+use crate::ConversionValueDefinition;
+
+/// Loads unit definitions from a file written in the GNU `units` definition format.
+///
+/// Only a subset of the format is supported: primitive units (`m !`), linear definitions
+/// (`inch 2.54 cm`), prefixes (`kilo- 1e3`) and aliases (`meter m`). Every unit is assigned the
+/// category of the primitive unit it is ultimately defined from. Entries that can't be
+/// represented, such as non-linear functions, tables or compound units, are reported as skipped
+/// rather than failing the whole load.
+pub struct GnuUnitsSource {
+    input: SourceInput,
+    categories: HashMap<String, String>,
+}
+
+#[derive(Debug, Default)]
+pub struct GnuUnitsDefinitions {
+    pub units: Vec<UnitAbbreviation>,
+    pub conversions: Vec<ConversionDefinition>,
+    pub skipped: Vec<SkippedDefinition>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct SkippedDefinition {
+    pub line: usize,
+    pub name: String,
+    pub reason: SkipReason,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum SkipReason {
+    Directive,
+    NonLinear,
+    Dimensionless,
+    Unsupported(String),
+    UnknownUnit(String),
+    Dependency(String),
+    NoCategory(String),
+    Redefinition,
+}
+
+impl Display for SkipReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Directive => f.write_str("directives are not supported"),
+            Self::NonLinear => f.write_str("non-linear definitions are not supported"),
+            Self::Dimensionless => f.write_str("dimensionless units are not supported"),
+            Self::Unsupported(def) => write!(f, "unsupported definition '{}'", def),
+            Self::UnknownUnit(unit) => write!(f, "unknown unit '{}'", unit),
+            Self::Dependency(unit) => write!(f, "depends on skipped unit '{}'", unit),
+            Self::NoCategory(primitive) => {
+                write!(f, "no category for primitive unit '{}'", primitive)
+            }
+            Self::Redefinition => f.write_str("unit has already been defined"),
+        }
+    }
+}
+
+const DEFAULT_CATEGORIES: [(&str, &str); 6] = [
+    ("m", "Length"),
+    ("kg", "Weight"),
+    ("s", "Time"),
+    ("A", "Current"),
+    ("mol", "Amount"),
+    ("cd", "Luminosity"),
+];
+
+impl GnuUnitsSource {
+    pub fn new(path: &str) -> GnuUnitsSource {
+        GnuUnitsSource::from_input(SourceInput::path(path))
+    }
+
+    pub fn from_contents(name: &str, contents: &str) -> GnuUnitsSource {
+        GnuUnitsSource::from_input(SourceInput::contents(name, contents))
+    }
+
+    pub fn from_reader(name: &str, reader: impl Read) -> Result<GnuUnitsSource, ConversionError> {
+        Ok(GnuUnitsSource::from_input(SourceInput::reader(
+            name, reader,
+        )?))
+    }
+
+    fn from_input(input: SourceInput) -> GnuUnitsSource {
+        GnuUnitsSource {
+            input,
+            categories: DEFAULT_CATEGORIES
+                .iter()
+                .map(|(primitive, category)| (primitive.to_string(), category.to_string()))
+                .collect(),
+        }
+    }
+
+    /// Assigns every unit derived from the primitive unit `primitive` to `category`.
+    pub fn category(mut self, primitive: &str, category: &str) -> GnuUnitsSource {
+        self.categories
+            .insert(primitive.to_owned(), category.to_owned());
+        self
+    }
+
+    pub fn load(&self) -> Result<GnuUnitsDefinitions, ConversionError> {
+        let contents = self.input.read()?;
+        let result = parse_definitions(&contents, &self.categories);
+
+        info!(
+            "Imported {} units and {} conversions from {} ({} entries skipped)",
+            result.units.len(),
+            result.conversions.len(),
+            self.input.name(),
+            result.skipped.len()
+        );
+        Ok(result)
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Definition {
+    Primitive,
+    Alias(String),
+    Linear(f64, String),
+}
+
+#[derive(Default)]
+struct Resolver {
+    definitions: HashMap<String, (usize, Definition)>,
+    prefixes: Vec<(String, f64)>,
+    resolved: HashMap<String, Result<(String, String), SkipReason>>,
+    visiting: HashSet<String>,
+    nodes: Vec<(String, String)>,
+    aliases: Vec<(String, String)>,
+    links: Vec<(String, String, f64)>,
+}
+
+fn parse_definitions(contents: &str, categories: &HashMap<String, String>) -> GnuUnitsDefinitions {
+    let mut result = GnuUnitsDefinitions::default();
+    let mut resolver = Resolver::default();
+    let mut prefix_definitions = vec![];
+    let mut order = vec![];
+
+    for (line, name, definition) in logical_lines(contents) {
+        let mut skip = |reason: SkipReason| {
+            trace!("Skipping '{}' on line {}: {}", name, line, reason);
+            result.skipped.push(SkippedDefinition {
+                line,
+                name: name.to_owned(),
+                reason,
+            });
+        };
+
+        if name.starts_with('!') {
+            skip(SkipReason::Directive);
+        } else if name.contains('(') || name.contains('[') {
+            skip(SkipReason::NonLinear);
+        } else if let Some(prefix) = name.strip_suffix('-') {
+            prefix_definitions.push((line, prefix.to_owned(), definition));
+        } else if resolver.definitions.contains_key(&name) {
+            skip(SkipReason::Redefinition);
+        } else {
+            match parse_definition(&definition) {
+                Ok(def) => {
+                    resolver.definitions.insert(name.clone(), (line, def));
+                    order.push(name);
+                }
+                Err(reason) => skip(reason),
+            }
+        }
+    }
+
+    for (line, name, reason) in resolver.resolve_prefixes(prefix_definitions) {
+        result
+            .skipped
+            .push(SkippedDefinition { line, name, reason });
+    }
+
+    for name in &order {
+        if let Err(reason) = resolver.resolve(name) {
+            let (line, _) = resolver.definitions[name];
+            result.skipped.push(SkippedDefinition {
+                line,
+                name: name.to_owned(),
+                reason,
+            });
+        }
+    }
+
+    let mut unit_categories = HashMap::new();
+    for (unit, primitive) in &resolver.nodes {
+        match categories.get(primitive) {
+            Some(category) => {
+                unit_categories.insert(unit.as_str(), category.as_str());
+                result.units.push(UnitAbbreviation {
+                    unit: unit.to_owned(),
+                    abbrev: unit.to_owned(),
+                    unit_type: category.to_owned(),
+                    region: None,
+                });
+            }
+            None => {
+                if let Some((line, _)) = resolver.definitions.get(unit) {
+                    result.skipped.push(SkippedDefinition {
+                        line: *line,
+                        name: unit.to_owned(),
+                        reason: SkipReason::NoCategory(primitive.to_owned()),
+                    });
+                }
+            }
+        }
+    }
+
+    for (alias, unit) in &resolver.aliases {
+        if let Some(category) = unit_categories.get(unit.as_str()) {
+            result.units.push(UnitAbbreviation {
+                unit: unit.to_owned(),
+                abbrev: alias.to_owned(),
+                unit_type: category.to_string(),
+                region: None,
+            });
+        }
+    }
+
+    for (from, to, multiplier) in resolver.links {
+        if let Some(category) = unit_categories.get(from.as_str()) {
+            debug!(
+                "Imported GNU conversion [{}] {} -> {}: {}",
+                category, from, to, multiplier
+            );
+            result.conversions.push(ConversionDefinition {
+                category: category.to_string(),
+                from,
+                to,
+                val: ConversionValueDefinition::Multiplier(multiplier),
+            });
+        }
+    }
+
+    result.skipped.sort_by_key(|s| s.line);
+    result
+}
+
+/// Splits the file into `(line, name, definition)` entries, joining lines continued with a
+/// trailing backslash and removing comments.
+fn logical_lines(contents: &str) -> Vec<(usize, String, String)> {
+    let mut result = vec![];
+    let mut current = String::new();
+    let mut start = 0;
+
+    for (i, line) in contents.lines().enumerate() {
+        if current.is_empty() {
+            start = i + 1;
+        }
+
+        let line = line.split('#').next().unwrap_or_default();
+        if let Some(continued) = line.trim_end().strip_suffix('\\') {
+            current.push_str(continued);
+            current.push(' ');
+            continue;
+        }
+        current.push_str(line);
+
+        let entry = std::mem::take(&mut current);
+        let mut parts = entry.trim().splitn(2, char::is_whitespace);
+        if let Some(name) = parts.next().filter(|n| !n.is_empty()) {
+            let definition = parts.next().unwrap_or_default().trim();
+            result.push((start, name.to_owned(), definition.to_owned()));
+        }
+    }
+    result
+}
+
+fn parse_definition(definition: &str) -> Result<Definition, SkipReason> {
+    if let Some(primitive) = definition.strip_prefix('!') {
+        return match primitive.trim() {
+            "" => Ok(Definition::Primitive),
+            _ => Err(SkipReason::Dimensionless),
+        };
+    }
+
+    if definition.contains(['(', ')', '^', '/', ';', '[', '+']) {
+        return Err(SkipReason::Unsupported(definition.to_owned()));
+    }
+
+    let mut multiplier = 1.0;
+    let mut unit: Option<&str> = None;
+    for token in definition.split(|c: char| c.is_whitespace() || c == '*') {
+        if token.is_empty() {
+            continue;
+        }
+
+        if let Some(value) = parse_number(token) {
+            multiplier *= value;
+        } else if unit.is_none() && !token.starts_with(|c: char| c.is_ascii_digit()) {
+            unit = Some(token);
+        } else {
+            return Err(SkipReason::Unsupported(definition.to_owned()));
+        }
+    }
+
+    match unit {
+        Some(unit) if multiplier == 1.0 && !definition.contains(char::is_whitespace) => {
+            Ok(Definition::Alias(unit.to_owned()))
+        }
+        Some(unit) => Ok(Definition::Linear(multiplier, unit.to_owned())),
+        None => Err(SkipReason::Dimensionless),
+    }
+}
+
+/// Parses a number, including the GNU `units` fraction syntax (`1|3`).
+fn parse_number(token: &str) -> Option<f64> {
+    match token.split_once('|') {
+        Some((numerator, denominator)) => {
+            Some(numerator.parse::<f64>().ok()? / denominator.parse::<f64>().ok()?)
+        }
+        None => token.parse::<f64>().ok(),
+    }
+}
+
+impl Resolver {
+    fn resolve_prefixes(
+        &mut self,
+        definitions: Vec<(usize, String, String)>,
+    ) -> Vec<(usize, String, SkipReason)> {
+        let mut values: HashMap<String, f64> = HashMap::new();
+        let mut pending = definitions;
+
+        // Prefixes may be defined in terms of other prefixes, so keep resolving until no
+        // further progress is made.
+        loop {
+            let before = pending.len();
+            pending.retain(|(_, name, definition)| {
+                let value = parse_number(definition)
+                    .or_else(|| values.get(definition.trim_end_matches('-')).copied());
+                match value {
+                    Some(value) => {
+                        values.insert(name.to_owned(), value);
+                        false
+                    }
+                    None => true,
+                }
+            });
+            if pending.len() == before {
+                break;
+            }
+        }
+
+        self.prefixes = values.into_iter().collect();
+        self.prefixes
+            .sort_by(|a, b| b.0.len().cmp(&a.0.len()).then_with(|| a.0.cmp(&b.0)));
+
+        pending
+            .into_iter()
+            .map(|(line, name, definition)| (line, name + "-", SkipReason::Unsupported(definition)))
+            .collect()
+    }
+
+    /// Resolves a defined unit, returning its canonical name and the primitive unit it is
+    /// derived from.
+    fn resolve(&mut self, name: &str) -> Result<(String, String), SkipReason> {
+        if let Some(result) = self.resolved.get(name) {
+            return result.clone();
+        }
+        if !self.visiting.insert(name.to_owned()) {
+            return Err(SkipReason::Unsupported(format!(
+                "circular definition of '{}'",
+                name
+            )));
+        }
+
+        let (_, definition) = self.definitions[name].clone();
+        let result = match definition {
+            Definition::Primitive => {
+                self.nodes.push((name.to_owned(), name.to_owned()));
+                Ok((name.to_owned(), name.to_owned()))
+            }
+            Definition::Alias(target) => self.resolve_reference(&target).inspect(|(unit, _)| {
+                self.aliases.push((name.to_owned(), unit.clone()));
+            }),
+            Definition::Linear(multiplier, target) => {
+                self.resolve_reference(&target).map(|(unit, primitive)| {
+                    self.nodes.push((name.to_owned(), primitive.clone()));
+                    self.links.push((name.to_owned(), unit, multiplier));
+                    (name.to_owned(), primitive)
+                })
+            }
+        };
+
+        self.visiting.remove(name);
+        self.resolved.insert(name.to_owned(), result.clone());
+        result
+    }
+
+    /// Resolves a unit referenced from another definition, which may be a prefixed form of a
+    /// defined unit (`cm`, `kilometer`). Prefixed units are only added when referenced.
+    fn resolve_reference(&mut self, name: &str) -> Result<(String, String), SkipReason> {
+        if self.definitions.contains_key(name) {
+            return self
+                .resolve(name)
+                .map_err(|_| SkipReason::Dependency(name.to_owned()));
+        }
+        if let Some(result) = self.resolved.get(name) {
+            return result.clone();
+        }
+
+        let prefixed = self.prefixes.iter().find_map(|(prefix, value)| {
+            name.strip_prefix(prefix.as_str())
+                .filter(|unit| self.definitions.contains_key(*unit))
+                .map(|unit| (unit.to_owned(), *value))
+        });
+
+        let result = match prefixed {
+            Some((unit, multiplier)) => self
+                .resolve(&unit)
+                .map_err(|_| SkipReason::Dependency(unit.to_owned()))
+                .map(|(unit, primitive)| {
+                    trace!("Adding prefixed unit {} ({} * {})", name, multiplier, unit);
+                    self.nodes.push((name.to_owned(), primitive.clone()));
+                    self.links.push((name.to_owned(), unit, multiplier));
+                    (name.to_owned(), primitive)
+                }),
+            None => {
+                warn!("Unable to resolve GNU units reference '{}'", name);
+                Err(SkipReason::UnknownUnit(name.to_owned()))
+            }
+        };
+
+        self.resolved.insert(name.to_owned(), result.clone());
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::converter::builder::UnitConverterBuilder;
+
+    const DEFINITIONS: &str = r"
+# Primitive units
+m                       !
+kg                      !
+radian                  !dimensionless
+
+meter                   m
+kilo-                   1e3
+k-                      kilo
+centi-                  1e-2
+c-                      centi
+milli-                  1e-3
+
+gram                    millikg
+g                       gram
+inch                    2.54 cm
+foot                    12 inch
+ft                      foot
+mile                    5280 \
+                        ft
+league                  3 mile
+third                   1|3 foot
+acre                    4840 yard^2
+tempC(x) units=[1;K]    x K + 273.15
+dozen                   12
+!utf8
+furlong                 220 yard
+ft                      0.3 m
+";
+
+    fn parse(contents: &str) -> GnuUnitsDefinitions {
+        GnuUnitsSource::from_contents("definitions.units", contents)
+            .load()
+            .unwrap()
+    }
+
+    fn find_conversion<'a>(
+        defs: &'a GnuUnitsDefinitions,
+        from: &str,
+    ) -> Option<&'a ConversionDefinition> {
+        defs.conversions.iter().find(|c| c.from == from)
+    }
+
+    #[test]
+    fn primitive_units() {
+        let defs = parse(DEFINITIONS);
+        let unit = defs.units.iter().find(|u| u.abbrev == "m").unwrap();
+        assert_eq!(unit.unit, "m");
+        assert_eq!(unit.unit_type, "Length");
+    }
+
+    #[test]
+    fn linear_definition() {
+        let defs = parse(DEFINITIONS);
+        let conversion = find_conversion(&defs, "foot").unwrap();
+        assert_eq!(conversion.to, "inch");
+        assert_eq!(conversion.category, "Length");
+        assert_eq!(conversion.val, ConversionValueDefinition::Multiplier(12.0));
+    }
+
+    #[test]
+    fn continuation_line() {
+        let defs = parse(DEFINITIONS);
+        let conversion = find_conversion(&defs, "mile").unwrap();
+        assert_eq!(conversion.to, "foot");
+        assert_eq!(
+            conversion.val,
+            ConversionValueDefinition::Multiplier(5280.0)
+        );
+    }
+
+    #[test]
+    fn fraction() {
+        let defs = parse(DEFINITIONS);
+        let conversion = find_conversion(&defs, "third").unwrap();
+        assert_eq!(
+            conversion.val,
+            ConversionValueDefinition::Multiplier(1.0 / 3.0)
+        );
+    }
+
+    #[test]
+    fn prefixed_reference() {
+        let defs = parse(DEFINITIONS);
+        let conversion = find_conversion(&defs, "cm").unwrap();
+        assert_eq!(conversion.to, "m");
+        assert_eq!(conversion.val, ConversionValueDefinition::Multiplier(0.01));
+
+        let conversion = find_conversion(&defs, "millikg").unwrap();
+        assert_eq!(conversion.to, "kg");
+        assert_eq!(conversion.category, "Weight");
+        assert_eq!(conversion.val, ConversionValueDefinition::Multiplier(1e-3));
+    }
+
+    #[test]
+    fn aliases() {
+        let defs = parse(DEFINITIONS);
+        let alias = defs.units.iter().find(|u| u.abbrev == "ft").unwrap();
+        assert_eq!(alias.unit, "foot");
+        assert!(find_conversion(&defs, "ft").is_none());
+
+        let alias = defs.units.iter().find(|u| u.abbrev == "g").unwrap();
+        assert_eq!(alias.unit, "millikg");
+    }
+
+    #[test]
+    fn skipped_definitions() {
+        let defs = parse(DEFINITIONS);
+        let skipped: Vec<(&str, SkipReason)> = defs
+            .skipped
+            .iter()
+            .map(|s| (s.name.as_str(), s.reason.clone()))
+            .collect();
+
+        let expected = vec![
+            ("radian", SkipReason::Dimensionless),
+            ("acre", SkipReason::Unsupported(String::from("4840 yard^2"))),
+            ("tempC(x)", SkipReason::NonLinear),
+            ("dozen", SkipReason::Dimensionless),
+            ("!utf8", SkipReason::Directive),
+            ("furlong", SkipReason::UnknownUnit(String::from("yard"))),
+            ("ft", SkipReason::Redefinition),
+        ];
+        assert_eq!(expected, skipped);
+    }
+
+    #[test]
+    fn unmapped_primitive() {
+        let defs = parse("bit !\nbyte 8 bit\n");
+        assert!(defs.units.is_empty());
+        assert_eq!(
+            defs.skipped[0].reason,
+            SkipReason::NoCategory(String::from("bit"))
+        );
+    }
+
+    #[test]
+    fn load_from_path() {
+        let path = std::env::temp_dir().join(format!(
+            "unitconvert_gnu_units_{}.units",
+            std::process::id()
+        ));
+        std::fs::write(&path, DEFINITIONS).unwrap();
+        let defs = GnuUnitsSource::new(path.to_str().unwrap()).load();
+        std::fs::remove_file(&path).unwrap();
+
+        let defs = defs.unwrap();
+        let expected = parse(DEFINITIONS);
+        assert_eq!(expected.units.len(), defs.units.len());
+        assert_eq!(expected.conversions.len(), defs.conversions.len());
+        assert_eq!(expected.skipped, defs.skipped);
+        let conversion = find_conversion(&defs, "foot").unwrap();
+        assert_eq!(conversion.val, ConversionValueDefinition::Multiplier(12.0));
+
+        let err = GnuUnitsSource::new(path.to_str().unwrap())
+            .load()
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("Error loading definitions from "));
+    }
+
+    #[test]
+    fn build_converter() {
+        let defs = parse(DEFINITIONS);
+        let mut converter = UnitConverterBuilder::new()
+            .reverse_base_conversions(true)
+            .add_unit_definitions(defs.units)
+            .add_base_conversions(defs.conversions)
+            .build()
+            .unwrap();
+
+        let actual = converter
+            .convert_from_definition("Length", "league", "m", 1.0)
+            .unwrap();
+        assert_eq!(4828.032, actual);
+    }
+}
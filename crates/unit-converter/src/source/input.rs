@@ -0,0 +1,62 @@
+use std::borrow::Cow;
+use std::fmt::Display;
+use std::io::Read;
+
+use crate::converter::error::ConversionError;
+
+/// The contents of a definition source, either read from a path when loaded or provided up
+/// front. The name is used to identify the source in log messages and errors.
+pub struct SourceInput {
+    name: String,
+    contents: Option<String>,
+}
+
+impl SourceInput {
+    pub fn path(path: &str) -> SourceInput {
+        SourceInput {
+            name: path.to_owned(),
+            contents: None,
+        }
+    }
+
+    pub fn contents(name: &str, contents: &str) -> SourceInput {
+        SourceInput {
+            name: name.to_owned(),
+            contents: Some(contents.to_owned()),
+        }
+    }
+
+    pub fn reader(name: &str, mut reader: impl Read) -> Result<SourceInput, ConversionError> {
+        let mut contents = String::new();
+        reader
+            .read_to_string(&mut contents)
+            .map_err(|err| error(name, err))?;
+        Ok(SourceInput::contents(name, &contents))
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn read(&self) -> Result<Cow<'_, str>, ConversionError> {
+        match &self.contents {
+            Some(contents) => Ok(Cow::Borrowed(contents)),
+            None => std::fs::read_to_string(&self.name)
+                .map(Cow::Owned)
+                .map_err(|err| error(&self.name, err)),
+        }
+    }
+
+    /// Reads the source and parses it, prefixing any error with the name of the source.
+    pub fn parse<T>(
+        &self,
+        parse: impl FnOnce(&str) -> Result<T, ConversionError>,
+    ) -> Result<T, ConversionError> {
+        let contents = self.read()?;
+        parse(&contents).map_err(|err| error(&self.name, err))
+    }
+}
+
+fn error(name: &str, err: impl Display) -> ConversionError {
+    ConversionError::new(&format!("Error loading definitions from {}: {}", name, err))
+}
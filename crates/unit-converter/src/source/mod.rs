@@ -1 +1,4 @@
+pub mod csv;
+pub mod gnu_units;
+mod input;
 pub mod toml;
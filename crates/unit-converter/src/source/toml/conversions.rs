@@ -1,66 +1,283 @@
+use std::io::Read;
+
 use log::info;
 use log::trace;
 use toml::{Table, Value};
 
-use crate::source::toml::parse_helper::parse_table;
+use crate::converter::error::ConversionError;
+use crate::log_scale::LogScale;
+use crate::source::input::SourceInput;
+use crate::source::toml::parse_helper::{parse_array, parse_table};
+use crate::table::{Interpolation, LookupTable};
 use crate::ConversionDefinition;
 use crate::ConversionValueDefinition;
 
 pub struct BaseConversionsSourceToml {
-    path: String,
+    input: SourceInput,
 }
 
 impl BaseConversionsSourceToml {
     pub fn new(path: &str) -> BaseConversionsSourceToml {
         BaseConversionsSourceToml {
-            path: path.to_owned(),
+            input: SourceInput::path(path),
         }
     }
 
-    pub fn load(
-        &self,
-    ) -> Result<Vec<ConversionDefinition>, crate::converter::error::ConversionError> {
-        let contents = std::fs::read_to_string(&self.path)?;
-        let config = contents.parse::<Table>()?;
-
-        let mut result = vec![];
-        for (category, units) in &config {
-            for (unit_from, conversions) in parse_table(units)? {
-                for (unit_to, value) in parse_table(conversions)? {
-                    trace!(
-                        "Imported Base Conversion: [{}] {} -> {}: {}",
-                        category,
-                        unit_from,
-                        unit_to,
-                        value
-                    );
-
-                    if let Some(c) = match_definition_val(value) {
-                        result.push(ConversionDefinition {
-                            val: c,
-                            from: unit_from.to_owned(),
-                            to: unit_to.to_owned(),
-                            category: category.to_owned(),
-                        });
-                    }
-                }
-            }
+    pub fn from_contents(name: &str, contents: &str) -> BaseConversionsSourceToml {
+        BaseConversionsSourceToml {
+            input: SourceInput::contents(name, contents),
         }
+    }
+
+    pub fn from_reader(
+        name: &str,
+        reader: impl Read,
+    ) -> Result<BaseConversionsSourceToml, ConversionError> {
+        Ok(BaseConversionsSourceToml {
+            input: SourceInput::reader(name, reader)?,
+        })
+    }
+
+    pub fn load(&self) -> Result<Vec<ConversionDefinition>, ConversionError> {
+        let result = self.input.parse(parse_conversions)?;
 
         info!(
             "Imported {} default unit conversions from {}",
             result.len(),
-            &self.path
+            self.input.name()
         );
         Ok(result)
     }
 }
 
-fn match_definition_val(val: &Value) -> Option<ConversionValueDefinition> {
-    match val {
+fn parse_conversions(contents: &str) -> Result<Vec<ConversionDefinition>, ConversionError> {
+    let config = contents.parse::<Table>()?;
+
+    let mut result = vec![];
+    for (category, units) in &config {
+        for (unit_from, conversions) in parse_table(units)? {
+            for (unit_to, value) in parse_table(conversions)? {
+                trace!(
+                    "Imported Base Conversion: [{}] {} -> {}: {}",
+                    category,
+                    unit_from,
+                    unit_to,
+                    value
+                );
+
+                if let Some(c) = match_definition_val(value)? {
+                    result.push(ConversionDefinition {
+                        val: c,
+                        from: unit_from.to_owned(),
+                        to: unit_to.to_owned(),
+                        category: category.to_owned(),
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+fn match_definition_val(val: &Value) -> Result<Option<ConversionValueDefinition>, ConversionError> {
+    let result = match val {
         Value::Float(f) => Some(ConversionValueDefinition::Multiplier(*f)),
         Value::Integer(i) => Some(ConversionValueDefinition::Multiplier(*i as f64)),
-        Value::String(s) => Some(ConversionValueDefinition::Expression(s.to_owned())),
+        Value::String(s) => Some(ConversionValueDefinition::Expression {
+            expr: s.to_owned(),
+            params: vec![],
+        }),
+        Value::Table(tbl) if tbl.contains_key("expr") => Some(match_expression(tbl)?),
+        Value::Table(tbl) if tbl.contains_key("table") => Some(match_lookup_table(tbl)?),
+        Value::Table(tbl) if tbl.contains_key("reference") => Some(match_log_scale(tbl)?),
+        Value::Table(tbl) if tbl.contains_key("approx") => Some(
+            ConversionValueDefinition::Approximate(parse_number(&tbl["approx"])?),
+        ),
+        Value::Table(tbl) => match tbl.get("reciprocal") {
+            Some(Value::Float(f)) => Some(ConversionValueDefinition::Reciprocal(*f)),
+            Some(Value::Integer(i)) => Some(ConversionValueDefinition::Reciprocal(*i as f64)),
+            _ => None,
+        },
         _ => None,
+    };
+    Ok(result)
+}
+
+/// Parses an expression with parameters of the form `{ expr = "pt * dpi / 72", params = { dpi = 96 } }`,
+/// where `params` contains the default value of each parameter.
+fn match_expression(tbl: &Table) -> Result<ConversionValueDefinition, ConversionError> {
+    let expr = match &tbl["expr"] {
+        Value::String(s) => s.to_owned(),
+        val => {
+            return Err(ConversionError::new(&format!(
+                "Expected a string but found {}",
+                val.type_str()
+            )))
+        }
+    };
+
+    let mut params = vec![];
+    if let Some(defaults) = tbl.get("params") {
+        for (name, default) in parse_table(defaults)? {
+            params.push((name.to_owned(), parse_number(default)?));
+        }
+    }
+    Ok(ConversionValueDefinition::Expression { expr, params })
+}
+
+/// Parses a lookup table of the form `{ table = [[x, y], ...], interpolation = "linear" }`.
+fn match_lookup_table(tbl: &Table) -> Result<ConversionValueDefinition, ConversionError> {
+    let interpolation = match tbl.get("interpolation") {
+        None => Interpolation::Linear,
+        Some(Value::String(s)) => Interpolation::parse(s).ok_or(ConversionError::new(&format!(
+            "Unknown interpolation '{}'",
+            s
+        )))?,
+        Some(val) => {
+            return Err(ConversionError::new(&format!(
+                "Expected a string but found {}",
+                val.type_str()
+            )))
+        }
+    };
+
+    let mut points = vec![];
+    for point in parse_array(&tbl["table"])? {
+        match parse_array(point)?.as_slice() {
+            [x, y] => points.push((parse_number(x)?, parse_number(y)?)),
+            _ => {
+                return Err(ConversionError::new(
+                    "Lookup table points must contain two values",
+                ))
+            }
+        }
+    }
+
+    Ok(ConversionValueDefinition::Table(LookupTable::new(
+        points,
+        interpolation,
+    )?))
+}
+
+/// Parses a logarithmic scale of the form `{ reference = 0.001, factor = 10, base = 10 }`, with
+/// the base and factor defaulting to 10. The conversion is from the level to the linear unit
+/// unless `inverse = true` is given.
+fn match_log_scale(tbl: &Table) -> Result<ConversionValueDefinition, ConversionError> {
+    let number_or = |key: &str, default: f64| match tbl.get(key) {
+        Some(val) => parse_number(val),
+        None => Ok(default),
+    };
+    let scale = LogScale::new(
+        number_or("base", 10.0)?,
+        number_or("factor", 10.0)?,
+        parse_number(&tbl["reference"])?,
+    )?;
+
+    let result = match tbl.get("inverse") {
+        None | Some(Value::Boolean(false)) => scale,
+        Some(Value::Boolean(true)) => scale.inverse(),
+        Some(val) => {
+            return Err(ConversionError::new(&format!(
+                "Expected a boolean but found {}",
+                val.type_str()
+            )))
+        }
+    };
+    Ok(ConversionValueDefinition::Logarithmic(result))
+}
+
+fn parse_number(val: &Value) -> Result<f64, ConversionError> {
+    match val {
+        Value::Float(f) => Ok(*f),
+        Value::Integer(i) => Ok(*i as f64),
+        _ => Err(ConversionError::new(&format!(
+            "Expected a number but found {}",
+            val.type_str()
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookup_table() {
+        let contents = "[Length.AmericanWireGauge.Millimeters]\ninterpolation = \"step\"\ntable = [[0, 8.2515], [1, 7.3481]]\n";
+        let actual = parse_conversions(contents).unwrap();
+
+        let expected =
+            LookupTable::new(vec![(0.0, 8.2515), (1.0, 7.3481)], Interpolation::Step).unwrap();
+        assert_eq!(1, actual.len());
+        assert_eq!(ConversionValueDefinition::Table(expected), actual[0].val);
+    }
+
+    #[test]
+    fn lookup_table_defaults_to_linear() {
+        let contents = "[Length]\nAmericanWireGauge = { Millimeters = { table = [[0, 8.2515], [1, 7.3481]] } }\n";
+        let actual = parse_conversions(contents).unwrap();
+
+        assert!(matches!(
+            &actual[0].val,
+            ConversionValueDefinition::Table(table) if table.interpolation == Interpolation::Linear
+        ));
+    }
+
+    #[test]
+    fn expression_with_params() {
+        let contents = "[Typography]\nPoints = { Pixels = { expr = \"pt * dpi / 72\", params = { dpi = 96 } } }\n";
+        let actual = parse_conversions(contents).unwrap();
+
+        let expected = ConversionValueDefinition::Expression {
+            expr: String::from("pt * dpi / 72"),
+            params: vec![(String::from("dpi"), 96.0)],
+        };
+        assert_eq!(expected, actual[0].val);
+    }
+
+    #[test]
+    fn approximate_multiplier() {
+        let contents = "[Length]\nMeters = { Inches = { approx = 39.3701 } }\n";
+        let actual = parse_conversions(contents).unwrap();
+
+        assert_eq!(
+            ConversionValueDefinition::Approximate(39.3701),
+            actual[0].val
+        );
+        let contents = "[Length]\nMeters = { Inches = { approx = \"39.3701\" } }\n";
+        assert!(parse_conversions(contents).is_err());
+    }
+
+    #[test]
+    fn log_scale() {
+        let contents = "[Power]\nDecibelMilliwatts = { Watts = { reference = 0.001 } }\n[Voltage]\nVolts = { DecibelVolts = { reference = 1, factor = 20, inverse = true } }\n";
+        let actual = parse_conversions(contents).unwrap();
+
+        let dbm = LogScale::new(10.0, 10.0, 0.001).unwrap();
+        let dbv = LogScale::new(10.0, 20.0, 1.0).unwrap().inverse();
+        assert_eq!(ConversionValueDefinition::Logarithmic(dbm), actual[0].val);
+        assert_eq!(ConversionValueDefinition::Logarithmic(dbv), actual[1].val);
+    }
+
+    #[test]
+    fn invalid_log_scale() {
+        let contents = "[Power]\nDecibelMilliwatts = { Watts = { reference = 0 } }\n";
+        assert!(parse_conversions(contents).is_err());
+    }
+
+    #[test]
+    fn invalid_lookup_table() {
+        for table in [
+            "table = [[1, 8.2515], [0, 7.3481]]",
+            "table = [[0, 8.2515, 1]]",
+            "table = [[0, 8.2515], [1, 7.3481]], interpolation = \"cubic\"",
+        ] {
+            let contents = format!(
+                "[Length]\nAmericanWireGauge = {{ Millimeters = {{ {} }} }}\n",
+                table
+            );
+            assert!(parse_conversions(&contents).is_err(), "{}", table);
+        }
     }
 }
@@ -0,0 +1,127 @@
+use std::io::Read;
+
+use log::{info, trace};
+use toml::{Table, Value};
+
+use crate::converter::error::ConversionError;
+use crate::currency::ExchangeRate;
+use crate::source::input::SourceInput;
+use crate::source::toml::parse_helper::parse_table;
+
+/// Loads exchange rates grouped by base currency and date, i.e.
+///
+/// ```toml
+/// [EUR.2024-03-01]
+/// USD = 1.0842
+/// ```
+pub struct ExchangeRateSourceToml {
+    input: SourceInput,
+}
+
+impl ExchangeRateSourceToml {
+    pub fn new(path: &str) -> ExchangeRateSourceToml {
+        ExchangeRateSourceToml {
+            input: SourceInput::path(path),
+        }
+    }
+
+    pub fn from_contents(name: &str, contents: &str) -> ExchangeRateSourceToml {
+        ExchangeRateSourceToml {
+            input: SourceInput::contents(name, contents),
+        }
+    }
+
+    pub fn from_reader(
+        name: &str,
+        reader: impl Read,
+    ) -> Result<ExchangeRateSourceToml, ConversionError> {
+        Ok(ExchangeRateSourceToml {
+            input: SourceInput::reader(name, reader)?,
+        })
+    }
+
+    pub fn load(&self) -> Result<Vec<ExchangeRate>, ConversionError> {
+        let result = self.input.parse(parse_rates)?;
+
+        info!(
+            "Imported {} exchange rates from {}",
+            result.len(),
+            self.input.name()
+        );
+        Ok(result)
+    }
+}
+
+fn parse_rates(contents: &str) -> Result<Vec<ExchangeRate>, ConversionError> {
+    let config = contents.parse::<Table>()?;
+
+    let mut result = vec![];
+    for (base, dates) in &config {
+        for (date, rates) in parse_table(dates)? {
+            let date = date
+                .parse()
+                .map_err(|_| ConversionError::new(&format!("'{}' is not a valid date", date)))?;
+
+            for (currency, rate) in parse_table(rates)? {
+                trace!(
+                    "Imported exchange rate {} {} -> {}: {}",
+                    date,
+                    base,
+                    currency,
+                    rate
+                );
+
+                let rate = match rate {
+                    Value::Float(f) => *f,
+                    Value::Integer(i) => *i as f64,
+                    _ => {
+                        return Err(ConversionError::new(&format!(
+                            "Expected a number but found {}",
+                            rate.type_str()
+                        )))
+                    }
+                };
+                result.push(ExchangeRate {
+                    date,
+                    base: base.to_owned(),
+                    currency: currency.to_owned(),
+                    rate,
+                });
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    #[test]
+    fn load_rates() {
+        let contents =
+            "[EUR.2024-03-01]\nUSD = 1.0842\nGBP = 0.8556\n\n[EUR.2024-03-04]\nUSD = 1\n";
+        let actual = ExchangeRateSourceToml::from_contents("rates", contents)
+            .load()
+            .unwrap();
+
+        assert_eq!(3, actual.len());
+        let expected = ExchangeRate {
+            date: NaiveDate::from_ymd_opt(2024, 3, 4).unwrap(),
+            base: String::from("EUR"),
+            currency: String::from("USD"),
+            rate: 1.0,
+        };
+        assert!(actual.contains(&expected));
+    }
+
+    #[test]
+    fn invalid_date() {
+        let contents = "[EUR.2024-13-01]\nUSD = 1.0842\n";
+        let actual = ExchangeRateSourceToml::from_contents("rates", contents).load();
+        assert!(actual.is_err());
+    }
+}
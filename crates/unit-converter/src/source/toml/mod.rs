@@ -1,4 +1,7 @@
 pub mod conversions;
+pub mod currency;
 mod parse_helper;
+pub mod substances;
+pub mod timezones;
+pub mod ucum;
 pub mod units;
-
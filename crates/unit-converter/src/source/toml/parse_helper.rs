@@ -7,12 +7,18 @@ pub fn parse_table(value: &Value) -> Result<&Map<String, Value>, ConversionError
     if let Value::Table(tbl) = value {
         return Ok(tbl);
     }
-    Err(ConversionError::default())
+    Err(ConversionError::new(&format!(
+        "Expected a table but found {}",
+        value.type_str()
+    )))
 }
 
 pub fn parse_array(value: &Value) -> Result<&Vec<Value>, ConversionError> {
     if let Value::Array(vec) = value {
         return Ok(vec);
     }
-    Err(ConversionError::default())
+    Err(ConversionError::new(&format!(
+        "Expected an array but found {}",
+        value.type_str()
+    )))
 }
@@ -0,0 +1,187 @@
+use std::collections::HashMap;
+use std::io::Read;
+
+use log::{info, trace};
+use toml::{Table, Value};
+
+use crate::converter::error::ConversionError;
+use crate::source::input::SourceInput;
+use crate::source::toml::parse_helper::{parse_array, parse_table};
+use crate::substance::{Bridge, Substance, SubstanceRegistry};
+
+pub struct SubstanceDefinitionSourceToml {
+    input: SourceInput,
+}
+
+impl SubstanceDefinitionSourceToml {
+    pub fn new(path: &str) -> SubstanceDefinitionSourceToml {
+        SubstanceDefinitionSourceToml {
+            input: SourceInput::path(path),
+        }
+    }
+
+    pub fn from_contents(name: &str, contents: &str) -> SubstanceDefinitionSourceToml {
+        SubstanceDefinitionSourceToml {
+            input: SourceInput::contents(name, contents),
+        }
+    }
+
+    pub fn from_reader(
+        name: &str,
+        reader: impl Read,
+    ) -> Result<SubstanceDefinitionSourceToml, ConversionError> {
+        Ok(SubstanceDefinitionSourceToml {
+            input: SourceInput::reader(name, reader)?,
+        })
+    }
+
+    pub fn load(&self) -> Result<SubstanceRegistry, ConversionError> {
+        let result = self.input.parse(parse_substances)?;
+
+        info!(
+            "Imported {} substances and {} bridges from {}",
+            result.substances.len(),
+            result.bridges.len(),
+            self.input.name()
+        );
+        Ok(result)
+    }
+}
+
+fn parse_substances(contents: &str) -> Result<SubstanceRegistry, ConversionError> {
+    let config = contents.parse::<Table>()?;
+
+    let mut result = SubstanceRegistry::default();
+    if let Some(bridges) = config.get("bridges") {
+        for bridge in parse_array(bridges)? {
+            let bridge = parse_table(bridge)?;
+            let (from_type, from_unit) = parse_unit(bridge, "from")?;
+            let (to_type, to_unit) = parse_unit(bridge, "to")?;
+            result.bridges.push(Bridge {
+                property: parse_string(bridge, "property")?,
+                from_type,
+                from_unit,
+                to_type,
+                to_unit,
+            });
+        }
+    }
+
+    if let Some(substances) = config.get("substances") {
+        for (name, values) in parse_table(substances)? {
+            trace!("Imported substance {}: {}", name, values);
+
+            let mut substance = Substance {
+                name: name.to_owned(),
+                aliases: vec![],
+                properties: HashMap::new(),
+            };
+            for (key, value) in parse_table(values)? {
+                match (key.as_str(), value) {
+                    ("aliases", aliases) => {
+                        for alias in parse_array(aliases)? {
+                            if let Value::String(alias) = alias {
+                                substance.aliases.push(alias.to_owned());
+                            }
+                        }
+                    }
+                    (_, Value::Float(f)) => _ = substance.properties.insert(key.to_owned(), *f),
+                    (_, Value::Integer(i)) => {
+                        _ = substance.properties.insert(key.to_owned(), *i as f64)
+                    }
+                    (_, value) => {
+                        return Err(ConversionError::new(&format!(
+                            "Expected a number for property {} of {} but found {}",
+                            key,
+                            name,
+                            value.type_str()
+                        )))
+                    }
+                }
+            }
+            result.substances.push(substance);
+        }
+    }
+
+    Ok(result)
+}
+
+fn parse_unit(table: &Table, key: &str) -> Result<(String, String), ConversionError> {
+    let unit = table
+        .get(key)
+        .ok_or(ConversionError::new(&format!(
+            "Bridge is missing a value for '{}'",
+            key
+        )))
+        .and_then(parse_table)?;
+    Ok((parse_string(unit, "category")?, parse_string(unit, "unit")?))
+}
+
+fn parse_string(table: &Table, key: &str) -> Result<String, ConversionError> {
+    match table.get(key) {
+        Some(Value::String(s)) => Ok(s.to_owned()),
+        Some(value) => Err(ConversionError::new(&format!(
+            "Expected a string for '{}' but found {}",
+            key,
+            value.type_str()
+        ))),
+        None => Err(ConversionError::new(&format!(
+            "Missing value for '{}'",
+            key
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTENTS: &str = r#"
+[[bridges]]
+property = "density"
+from = { category = "Capacity", unit = "Liters" }
+to = { category = "Weight", unit = "Kilograms" }
+
+[substances]
+water = { density = 1 }
+flour = { density = 0.593, aliases = ["plain flour"] }
+"#;
+
+    #[test]
+    fn load_substances() {
+        let actual = SubstanceDefinitionSourceToml::from_contents("substances", CONTENTS)
+            .load()
+            .unwrap();
+
+        assert_eq!(2, actual.substances.len());
+        let flour = actual.substance("plain flour").unwrap();
+        assert_eq!(Some(&0.593), flour.properties.get("density"));
+        assert_eq!(
+            Some(&1.0),
+            actual.substance("water").unwrap().properties.get("density")
+        );
+
+        assert_eq!(1, actual.bridges.len());
+        assert_eq!("Kilograms", actual.bridges[0].to_unit);
+    }
+
+    #[test]
+    fn invalid_property() {
+        let actual = SubstanceDefinitionSourceToml::from_contents(
+            "substances",
+            "[substances]\nwater = { density = 'high' }\n",
+        )
+        .load();
+        assert!(actual.is_err());
+    }
+
+    #[test]
+    fn bridge_missing_unit() {
+        let actual = SubstanceDefinitionSourceToml::from_contents(
+            "substances",
+            "[[bridges]]\nproperty = 'density'\nfrom = { category = 'Capacity' }\n",
+        )
+        .load();
+        assert!(actual.is_err());
+    }
+}
@@ -0,0 +1,122 @@
+use std::io::Read;
+
+use chrono_tz::Tz;
+use log::{info, trace};
+use toml::{Table, Value};
+
+use crate::converter::error::ConversionError;
+use crate::source::input::SourceInput;
+use crate::source::toml::parse_helper::{parse_array, parse_table};
+use crate::timezone::TimeZoneAlias;
+
+/// Loads abbreviations for time zones, where abbreviations used by several time zones are given
+/// as an array with the default time zone first, i.e.
+///
+/// ```toml
+/// [aliases]
+/// JST = "Asia/Tokyo"
+/// CST = ["Etc/GMT+6", "Asia/Shanghai"]
+/// ```
+pub struct TimeZoneAliasSourceToml {
+    input: SourceInput,
+}
+
+impl TimeZoneAliasSourceToml {
+    pub fn new(path: &str) -> TimeZoneAliasSourceToml {
+        TimeZoneAliasSourceToml {
+            input: SourceInput::path(path),
+        }
+    }
+
+    pub fn from_contents(name: &str, contents: &str) -> TimeZoneAliasSourceToml {
+        TimeZoneAliasSourceToml {
+            input: SourceInput::contents(name, contents),
+        }
+    }
+
+    pub fn from_reader(
+        name: &str,
+        reader: impl Read,
+    ) -> Result<TimeZoneAliasSourceToml, ConversionError> {
+        Ok(TimeZoneAliasSourceToml {
+            input: SourceInput::reader(name, reader)?,
+        })
+    }
+
+    pub fn load(&self) -> Result<Vec<TimeZoneAlias>, ConversionError> {
+        let result = self.input.parse(parse_aliases)?;
+
+        info!(
+            "Imported {} time zone aliases from {}",
+            result.len(),
+            self.input.name()
+        );
+        Ok(result)
+    }
+}
+
+fn parse_aliases(contents: &str) -> Result<Vec<TimeZoneAlias>, ConversionError> {
+    let config = contents.parse::<Table>()?;
+
+    let mut result = vec![];
+    if let Some(aliases) = config.get("aliases") {
+        for (abbrev, zones) in parse_table(aliases)? {
+            trace!("Imported time zone alias {}: {}", abbrev, zones);
+
+            let zones = match zones {
+                Value::Array(_) => parse_array(zones)?.iter().map(parse_zone).collect(),
+                _ => parse_zone(zones).map(|zone| vec![zone]),
+            }?;
+            if zones.is_empty() {
+                return Err(ConversionError::new(&format!(
+                    "No time zones given for {}",
+                    abbrev
+                )));
+            }
+            result.push(TimeZoneAlias {
+                abbrev: abbrev.to_owned(),
+                zones,
+            });
+        }
+    }
+
+    Ok(result)
+}
+
+fn parse_zone(value: &Value) -> Result<Tz, ConversionError> {
+    match value {
+        Value::String(name) => name
+            .parse()
+            .map_err(|_| ConversionError::new(&format!("'{}' is not a valid time zone", name))),
+        _ => Err(ConversionError::new(&format!(
+            "Expected a time zone name but found {}",
+            value.type_str()
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_aliases() {
+        let contents = "[aliases]\nJST = 'Asia/Tokyo'\nIST = ['Asia/Kolkata', 'Europe/Dublin']\n";
+        let actual = TimeZoneAliasSourceToml::from_contents("aliases", contents)
+            .load()
+            .unwrap();
+
+        let ist = actual.iter().find(|a| a.abbrev == "IST").unwrap();
+        assert_eq!(vec![Tz::Asia__Kolkata, Tz::Europe__Dublin], ist.zones);
+        assert!(ist.is_ambiguous());
+        let jst = actual.iter().find(|a| a.abbrev == "JST").unwrap();
+        assert!(!jst.is_ambiguous());
+    }
+
+    #[test]
+    fn unknown_time_zone() {
+        let contents = "[aliases]\nMST = 'Mars/Olympus'\n";
+        let actual = TimeZoneAliasSourceToml::from_contents("aliases", contents).load();
+        assert!(actual.is_err());
+    }
+}
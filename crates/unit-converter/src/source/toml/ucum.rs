@@ -0,0 +1,130 @@
+use std::io::Read;
+
+use log::{info, trace};
+use toml::{map::Map, Table, Value};
+
+use crate::converter::error::ConversionError;
+use crate::source::input::SourceInput;
+use crate::source::toml::parse_helper::{parse_array, parse_table};
+use crate::ucum::UcumCode;
+
+/// Loads UCUM codes for units grouped by type. Codes that can't take a prefix (i.e. "d" for days,
+/// so that "cd" isn't read as centidays) are given as a table marking them as not metric, i.e.
+///
+/// ```toml
+/// [Time]
+/// Seconds = ['s']
+/// Days = { metric = false, codes = ['d'] }
+/// ```
+///
+/// Codes in square brackets (i.e. "[lb_av]") are never metric.
+pub struct UcumDefinitionSourceToml {
+    input: SourceInput,
+}
+
+impl UcumDefinitionSourceToml {
+    pub fn new(path: &str) -> UcumDefinitionSourceToml {
+        UcumDefinitionSourceToml {
+            input: SourceInput::path(path),
+        }
+    }
+
+    pub fn from_contents(name: &str, contents: &str) -> UcumDefinitionSourceToml {
+        UcumDefinitionSourceToml {
+            input: SourceInput::contents(name, contents),
+        }
+    }
+
+    pub fn from_reader(
+        name: &str,
+        reader: impl Read,
+    ) -> Result<UcumDefinitionSourceToml, ConversionError> {
+        Ok(UcumDefinitionSourceToml {
+            input: SourceInput::reader(name, reader)?,
+        })
+    }
+
+    pub fn load(&self) -> Result<Vec<UcumCode>, ConversionError> {
+        info!("Loading UCUM codes from {}", self.input.name());
+        self.input.parse(parse_codes)
+    }
+}
+
+fn parse_codes(contents: &str) -> Result<Vec<UcumCode>, ConversionError> {
+    let config = contents.parse::<Table>()?;
+
+    let mut result = vec![];
+    for (category, units) in &config {
+        for (unit, codes) in parse_table(units)? {
+            trace!("Loading UCUM codes {:?} for unit {}", &codes, &unit);
+
+            let (metric, codes) = match codes {
+                Value::Table(variant) => parse_variant(variant)?,
+                _ => (true, codes),
+            };
+            for value in parse_array(codes)? {
+                if let Value::String(code) = value {
+                    result.push(UcumCode {
+                        unit: unit.to_owned(),
+                        code: code.to_owned(),
+                        unit_type: category.to_owned(),
+                        metric: metric && !code.starts_with('['),
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+fn parse_variant(variant: &Map<String, Value>) -> Result<(bool, &Value), ConversionError> {
+    let metric = match variant.get("metric") {
+        Some(Value::Boolean(metric)) => *metric,
+        Some(value) => {
+            return Err(ConversionError::new(&format!(
+                "Expected metric to be a boolean but found {}",
+                value.type_str()
+            )))
+        }
+        None => true,
+    };
+    match variant.get("codes") {
+        Some(codes) => Ok((metric, codes)),
+        None => Err(ConversionError::new("No codes given for unit")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_metric_flags() {
+        let source = UcumDefinitionSourceToml::from_contents(
+            "inline",
+            "[Time]\nSeconds = ['s']\nDays = { metric = false, codes = ['d'] }\n\n[Weight]\nPounds = ['[lb_av]']\n",
+        );
+        let actual = source.load().unwrap();
+
+        assert_eq!(3, actual.len());
+        let metric = |code: &str| actual.iter().find(|c| c.code == code).unwrap().metric;
+        assert!(metric("s"));
+        assert!(!metric("d"));
+        assert!(!metric("[lb_av]"));
+    }
+
+    #[test]
+    fn error_non_boolean_metric_flag() {
+        let source = UcumDefinitionSourceToml::from_contents(
+            "inline",
+            "[Time]\nDays = { metric = 'no', codes = ['d'] }\n",
+        );
+        let actual = source.load().unwrap_err().to_string();
+
+        assert_eq!(
+            "Error loading definitions from inline: Expected metric to be a boolean but found string",
+            actual
+        );
+    }
+}
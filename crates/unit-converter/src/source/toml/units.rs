@@ -1,47 +1,179 @@
+use std::io::Read;
+
 use log::{info, trace};
-use toml::{Table, Value};
+use toml::{map::Map, Table, Value};
 
 use crate::converter::error::ConversionError;
 use crate::parser::UnitAbbreviation;
+use crate::source::input::SourceInput;
 use crate::source::toml::parse_helper::{parse_array, parse_table};
 
+/// Loads abbreviations for units grouped by type. Regional variants of a unit (i.e. US and
+/// Imperial gallons) are given as a table with the region, and can also be referred to by their
+/// abbreviations qualified with the region, i.e.
+///
+/// ```toml
+/// [Capacity]
+/// Liters = ['l']
+/// USGallons = { region = 'us', abbrevs = ['gal'] }
+/// ImperialGallons = { region = 'imp', abbrevs = ['gal'] }
+/// ```
+///
+/// where "gal" is resolved using the converter's default region, and "gal_us" and "gal_imp"
+/// always refer to the same unit.
 pub struct UnitDefinitionSourceToml {
-    path: String,
+    input: SourceInput,
 }
 
 impl UnitDefinitionSourceToml {
     pub fn new(path: &str) -> UnitDefinitionSourceToml {
         UnitDefinitionSourceToml {
-            path: path.to_owned(),
+            input: SourceInput::path(path),
+        }
+    }
+
+    pub fn from_contents(name: &str, contents: &str) -> UnitDefinitionSourceToml {
+        UnitDefinitionSourceToml {
+            input: SourceInput::contents(name, contents),
         }
     }
 
+    pub fn from_reader(
+        name: &str,
+        reader: impl Read,
+    ) -> Result<UnitDefinitionSourceToml, ConversionError> {
+        Ok(UnitDefinitionSourceToml {
+            input: SourceInput::reader(name, reader)?,
+        })
+    }
+
     pub fn load(&self) -> Result<Vec<crate::parser::UnitAbbreviation>, ConversionError> {
-        info!("Loading unit abbreviations");
-        let contents = std::fs::read_to_string(&self.path)?;
-        let config = contents.parse::<Table>()?;
-
-        let mut result = vec![];
-        for (category, units) in &config {
-            for (unit, abbreviations) in parse_table(units)? {
-                trace!(
-                    "Loading abbreviations {:?} for unit {}",
-                    &abbreviations,
-                    &unit
-                );
-
-                for value in parse_array(abbreviations)? {
-                    if let Value::String(abbrev) = value {
+        info!("Loading unit abbreviations from {}", self.input.name());
+        self.input.parse(parse_units)
+    }
+}
+
+fn parse_units(contents: &str) -> Result<Vec<UnitAbbreviation>, ConversionError> {
+    let config = contents.parse::<Table>()?;
+
+    let mut result = vec![];
+    for (category, units) in &config {
+        for (unit, abbreviations) in parse_table(units)? {
+            trace!(
+                "Loading abbreviations {:?} for unit {}",
+                &abbreviations,
+                &unit
+            );
+
+            let (region, abbreviations) = match abbreviations {
+                Value::Table(variant) => parse_variant(variant)?,
+                _ => (None, abbreviations),
+            };
+            for value in parse_array(abbreviations)? {
+                if let Value::String(abbrev) = value {
+                    result.push(UnitAbbreviation {
+                        unit: unit.to_owned(),
+                        abbrev: abbrev.to_owned(),
+                        unit_type: category.to_owned(),
+                        region: region.clone(),
+                    });
+                    if let Some(region) = &region {
                         result.push(UnitAbbreviation {
                             unit: unit.to_owned(),
-                            abbrev: abbrev.to_owned(),
+                            abbrev: format!("{}_{}", abbrev, region),
                             unit_type: category.to_owned(),
+                            region: Some(region.to_owned()),
                         });
                     }
                 }
             }
         }
+    }
+
+    Ok(result)
+}
+
+fn parse_variant(
+    variant: &Map<String, Value>,
+) -> Result<(Option<String>, &Value), ConversionError> {
+    let region = match variant.get("region") {
+        Some(Value::String(region)) => region.to_owned(),
+        Some(value) => {
+            return Err(ConversionError::new(&format!(
+                "Expected a region but found {}",
+                value.type_str()
+            )))
+        }
+        None => return Err(ConversionError::new("Regional units must have a region")),
+    };
+    match variant.get("abbrevs") {
+        Some(abbreviations) => Ok((Some(region), abbreviations)),
+        None => Err(ConversionError::new(&format!(
+            "No abbreviations given for region {}",
+            region
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_from_contents() {
+        let source = UnitDefinitionSourceToml::from_contents(
+            "inline",
+            "[Length]\nMeters = ['m', 'metre']\n",
+        );
+        let actual = source.load().unwrap();
+
+        assert_eq!(2, actual.len());
+        assert_eq!("Meters", actual[1].unit);
+        assert_eq!("metre", actual[1].abbrev);
+        assert_eq!("Length", actual[1].unit_type);
+    }
+
+    #[test]
+    fn load_regional_variants() {
+        let source = UnitDefinitionSourceToml::from_contents(
+            "inline",
+            "[Capacity]\nUSGallons = { region = 'us', abbrevs = ['gal'] }\n",
+        );
+        let actual = source.load().unwrap();
+
+        assert_eq!(2, actual.len());
+        assert_eq!("gal", actual[0].abbrev);
+        assert_eq!("gal_us", actual[1].abbrev);
+        assert_eq!(Some(String::from("us")), actual[1].region);
+    }
+
+    #[test]
+    fn load_from_reader() {
+        let contents = "[Weight]\nGrams = ['g']\n";
+        let source = UnitDefinitionSourceToml::from_reader("reader", contents.as_bytes()).unwrap();
+        let actual = source.load().unwrap();
+
+        assert_eq!(1, actual.len());
+        assert_eq!("Grams", actual[0].unit);
+    }
+
+    #[test]
+    fn error_names_source() {
+        let source =
+            UnitDefinitionSourceToml::from_contents("Custom.toml", "[Length]\nMeters = 'm'\n");
+        let actual = source.load().unwrap_err().to_string();
+
+        assert_eq!(
+            "Error loading definitions from Custom.toml: Expected an array but found string",
+            actual
+        );
+    }
+
+    #[test]
+    fn error_names_missing_path() {
+        let source = UnitDefinitionSourceToml::new("does/not/exist.toml");
+        let actual = source.load().unwrap_err().to_string();
 
-        Ok(result)
+        assert!(actual.starts_with("Error loading definitions from does/not/exist.toml: "));
     }
 }
@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+
+#[derive(Debug, Clone)]
+pub struct Substance {
+    pub name: String,
+    pub aliases: Vec<String>,
+    pub properties: HashMap<String, f64>,
+}
+
+/// Links a unit in one category to a unit in another using a property of a substance, where one
+/// `from_unit` of the substance is equal to the value of the property in `to_unit`s.
+#[derive(Debug, Clone)]
+pub struct Bridge {
+    pub property: String,
+    pub from_type: String,
+    pub from_unit: String,
+    pub to_type: String,
+    pub to_unit: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SubstanceRegistry {
+    pub substances: Vec<Substance>,
+    pub bridges: Vec<Bridge>,
+}
+
+impl SubstanceRegistry {
+    pub fn extend(&mut self, mut other: SubstanceRegistry) {
+        self.substances.append(&mut other.substances);
+        self.bridges.append(&mut other.bridges);
+    }
+
+    /// Finds a substance by its name or one of its aliases, ignoring case.
+    pub fn substance(&self, name: &str) -> Option<&Substance> {
+        let name = name.to_lowercase();
+        self.substances.iter().find(|substance| {
+            substance.name.to_lowercase() == name
+                || substance.aliases.iter().any(|a| a.to_lowercase() == name)
+        })
+    }
+
+    /// Finds the bridge between two categories. The returned flag is set when the bridge links
+    /// the categories in the opposite direction, so the property needs to be divided by instead.
+    pub fn bridge(&self, from_type: &str, to_type: &str) -> Option<(&Bridge, bool)> {
+        self.bridges.iter().find_map(|bridge| {
+            if bridge.from_type == from_type && bridge.to_type == to_type {
+                Some((bridge, false))
+            } else if bridge.from_type == to_type && bridge.to_type == from_type {
+                Some((bridge, true))
+            } else {
+                None
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn construct_registry() -> SubstanceRegistry {
+        SubstanceRegistry {
+            substances: vec![Substance {
+                name: String::from("flour"),
+                aliases: vec![String::from("Plain Flour")],
+                properties: HashMap::from([(String::from("density"), 0.593)]),
+            }],
+            bridges: vec![Bridge {
+                property: String::from("density"),
+                from_type: String::from("Capacity"),
+                from_unit: String::from("Liters"),
+                to_type: String::from("Weight"),
+                to_unit: String::from("Kilograms"),
+            }],
+        }
+    }
+
+    #[test]
+    fn substance_by_alias() {
+        let registry = construct_registry();
+        assert_eq!("flour", registry.substance("plain flour").unwrap().name);
+        assert_eq!("flour", registry.substance("Flour").unwrap().name);
+        assert!(registry.substance("sugar").is_none());
+    }
+
+    #[test]
+    fn bridge_in_either_direction() {
+        let registry = construct_registry();
+        let (bridge, reversed) = registry.bridge("Capacity", "Weight").unwrap();
+        assert_eq!("Liters", bridge.from_unit);
+        assert!(!reversed);
+
+        let (_, reversed) = registry.bridge("Weight", "Capacity").unwrap();
+        assert!(reversed);
+        assert!(registry.bridge("Weight", "Length").is_none());
+    }
+}
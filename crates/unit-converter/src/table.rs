@@ -0,0 +1,188 @@
+use std::fmt::Display;
+
+use crate::converter::error::ConversionError;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Interpolation {
+    Nearest,
+    Linear,
+    Step,
+}
+
+impl Interpolation {
+    pub fn parse(input: &str) -> Option<Interpolation> {
+        match input {
+            "nearest" => Some(Interpolation::Nearest),
+            "linear" => Some(Interpolation::Linear),
+            "step" => Some(Interpolation::Step),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Interpolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Interpolation::Nearest => f.write_str("nearest"),
+            Interpolation::Linear => f.write_str("linear"),
+            Interpolation::Step => f.write_str("step"),
+        }
+    }
+}
+
+/// A conversion defined by a table of points sorted by their input value, for conversions
+/// which can't be expressed as a formula (i.e. wire gauges).
+#[derive(Debug, PartialEq, Clone)]
+pub struct LookupTable {
+    pub points: Vec<(f64, f64)>,
+    pub interpolation: Interpolation,
+}
+
+impl LookupTable {
+    pub fn new(
+        points: Vec<(f64, f64)>,
+        interpolation: Interpolation,
+    ) -> Result<LookupTable, ConversionError> {
+        if points.len() < 2 {
+            return Err(ConversionError::new(
+                "Lookup tables must contain at least two points",
+            ));
+        }
+        if points.windows(2).any(|w| w[0].0 >= w[1].0) {
+            return Err(ConversionError::new(
+                "Lookup table points must be sorted by their input value",
+            ));
+        }
+        Ok(LookupTable {
+            points,
+            interpolation,
+        })
+    }
+
+    /// Creates the table for the opposite conversion. Only tables where the output values are
+    /// strictly increasing or decreasing can be inverted.
+    pub fn inverse(&self) -> Option<LookupTable> {
+        let mut points: Vec<(f64, f64)> = self.points.iter().map(|(x, y)| (*y, *x)).collect();
+        if points.windows(2).all(|w| w[0].0 > w[1].0) {
+            points.reverse();
+        }
+        LookupTable::new(points, self.interpolation).ok()
+    }
+
+    pub fn lookup(&self, value: f64) -> Result<f64, ConversionError> {
+        let (min, _) = self.points[0];
+        let (max, last) = self.points[self.points.len() - 1];
+        // The last step has no upper bound (i.e. force 12 on the Beaufort scale).
+        if self.interpolation == Interpolation::Step && value > max {
+            return Ok(last);
+        }
+        if !(min..=max).contains(&value) {
+            return Err(ConversionError::new(&format!(
+                "Value {} is outside the range of the lookup table ({} to {})",
+                value, min, max
+            )));
+        }
+
+        // Index of the last point with an input value less than or equal to the value.
+        let i = self.points.partition_point(|(x, _)| *x <= value) - 1;
+        let (x0, y0) = self.points[i];
+        if x0 == value || i == self.points.len() - 1 {
+            return Ok(y0);
+        }
+
+        let (x1, y1) = self.points[i + 1];
+        let result = match self.interpolation {
+            Interpolation::Step => y0,
+            Interpolation::Nearest if value - x0 <= x1 - value => y0,
+            Interpolation::Nearest => y1,
+            Interpolation::Linear => y0 + (value - x0) * (y1 - y0) / (x1 - x0),
+        };
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn construct_table(interpolation: Interpolation) -> LookupTable {
+        LookupTable::new(vec![(0.0, 10.0), (2.0, 20.0), (4.0, 60.0)], interpolation).unwrap()
+    }
+
+    #[test]
+    fn exact_point() {
+        let table = construct_table(Interpolation::Linear);
+        assert_eq!(20.0, table.lookup(2.0).unwrap());
+        assert_eq!(60.0, table.lookup(4.0).unwrap());
+    }
+
+    #[test]
+    fn linear_interpolation() {
+        let table = construct_table(Interpolation::Linear);
+        assert_eq!(15.0, table.lookup(1.0).unwrap());
+        assert_eq!(50.0, table.lookup(3.5).unwrap());
+    }
+
+    #[test]
+    fn step_interpolation() {
+        let table = construct_table(Interpolation::Step);
+        assert_eq!(10.0, table.lookup(1.9).unwrap());
+        assert_eq!(20.0, table.lookup(3.5).unwrap());
+    }
+
+    #[test]
+    fn step_above_last_point() {
+        let table = construct_table(Interpolation::Step);
+        assert_eq!(60.0, table.lookup(100.0).unwrap());
+        assert!(table.lookup(-0.1).is_err());
+    }
+
+    #[test]
+    fn nearest_interpolation() {
+        let table = construct_table(Interpolation::Nearest);
+        assert_eq!(10.0, table.lookup(0.9).unwrap());
+        assert_eq!(20.0, table.lookup(1.1).unwrap());
+        assert_eq!(60.0, table.lookup(3.1).unwrap());
+    }
+
+    #[test]
+    fn out_of_range() {
+        let table = construct_table(Interpolation::Linear);
+        let actual = table.lookup(4.5);
+        assert!(actual.is_err());
+        assert_eq!(
+            "Value 4.5 is outside the range of the lookup table (0 to 4)",
+            actual.unwrap_err().to_string()
+        );
+        assert!(table.lookup(-0.1).is_err());
+    }
+
+    #[test]
+    fn unsorted_points() {
+        let actual = LookupTable::new(vec![(1.0, 1.0), (0.0, 2.0)], Interpolation::Linear);
+        assert!(actual.is_err());
+    }
+
+    #[test]
+    fn inverse_of_decreasing_table() {
+        let table = LookupTable::new(
+            vec![(0.0, 8.0), (1.0, 4.0), (2.0, 2.0)],
+            Interpolation::Linear,
+        )
+        .unwrap();
+        let inverse = table.inverse().unwrap();
+
+        assert_eq!(vec![(2.0, 2.0), (4.0, 1.0), (8.0, 0.0)], inverse.points);
+        assert_eq!(1.5, inverse.lookup(3.0).unwrap());
+    }
+
+    #[test]
+    fn inverse_of_non_monotonic_table() {
+        let table = LookupTable::new(
+            vec![(0.0, 1.0), (1.0, 4.0), (2.0, 2.0)],
+            Interpolation::Linear,
+        )
+        .unwrap();
+        assert_eq!(None, table.inverse());
+    }
+}
@@ -0,0 +1,176 @@
+use chrono::{LocalResult, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+
+use crate::converter::error::ConversionError;
+
+pub const TIME_ZONE_UNIT_TYPE: &str = "Time Zone";
+
+/// An abbreviation for one or more time zones (i.e. PST or CET). Abbreviations used by several
+/// time zones are ambiguous, and resolve to the first time zone.
+#[derive(Debug, PartialEq, Clone)]
+pub struct TimeZoneAlias {
+    pub abbrev: String,
+    pub zones: Vec<Tz>,
+}
+
+impl TimeZoneAlias {
+    pub fn is_ambiguous(&self) -> bool {
+        self.zones.len() > 1
+    }
+}
+
+/// A wall-clock time converted between time zones.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ZonedConversion {
+    pub from: Tz,
+    pub to: Tz,
+    pub time: NaiveDateTime,
+    pub warnings: Vec<String>,
+}
+
+/// Finds a time zone by its IANA name (i.e. "America/New_York") or by an alias, adding a warning
+/// if the alias is ambiguous.
+pub fn resolve_time_zone(
+    aliases: &[TimeZoneAlias],
+    input: &str,
+    warnings: &mut Vec<String>,
+) -> Result<Tz, ConversionError> {
+    if let Some(alias) = aliases.iter().find(|a| a.abbrev == input) {
+        if alias.is_ambiguous() {
+            let others: Vec<&str> = alias.zones[1..].iter().map(|z| z.name()).collect();
+            warnings.push(format!(
+                "'{}' is ambiguous, using {} (could also be {})",
+                input,
+                alias.zones[0].name(),
+                others.join(", ")
+            ));
+        }
+        return Ok(alias.zones[0]);
+    }
+
+    input
+        .parse::<Tz>()
+        .or_else(|_| Tz::from_str_insensitive(input))
+        .map_err(|_| ConversionError::new(&format!("'{}' is not a valid time zone", input)))
+}
+
+/// Converts a wall-clock time between time zones on a date, which defaults to the current date in
+/// the source time zone.
+pub fn convert_time(
+    from: Tz,
+    to: Tz,
+    time: NaiveTime,
+    date: Option<NaiveDate>,
+) -> Result<ZonedConversion, ConversionError> {
+    let date = date.unwrap_or_else(|| Utc::now().with_timezone(&from).date_naive());
+    let local = date.and_time(time);
+
+    let mut warnings = vec![];
+    let source = match from.from_local_datetime(&local) {
+        LocalResult::Single(source) => source,
+        // Times repeated when the clocks go back use the first occurrence.
+        LocalResult::Ambiguous(earliest, latest) => {
+            warnings.push(format!(
+                "{} occurs twice in {}, using {} rather than {}",
+                local,
+                from.name(),
+                earliest.format("%Z"),
+                latest.format("%Z")
+            ));
+            earliest
+        }
+        LocalResult::None => {
+            return Err(ConversionError::new(&format!(
+                "{} doesn't exist in {} as the clocks go forward",
+                local,
+                from.name()
+            )))
+        }
+    };
+
+    Ok(ZonedConversion {
+        from,
+        to,
+        time: source.with_timezone(&to).naive_local(),
+        warnings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn construct_aliases() -> Vec<TimeZoneAlias> {
+        vec![
+            TimeZoneAlias {
+                abbrev: String::from("PST"),
+                zones: vec![Tz::Etc__GMTPlus8],
+            },
+            TimeZoneAlias {
+                abbrev: String::from("IST"),
+                zones: vec![Tz::Asia__Kolkata, Tz::Europe__Dublin],
+            },
+        ]
+    }
+
+    fn datetime(input: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(input, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn resolve_name() {
+        let mut warnings = vec![];
+        let actual = resolve_time_zone(&construct_aliases(), "asia/tokyo", &mut warnings);
+        assert_eq!(Tz::Asia__Tokyo, actual.unwrap());
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn resolve_ambiguous_alias() {
+        let mut warnings = vec![];
+        let actual = resolve_time_zone(&construct_aliases(), "IST", &mut warnings);
+        assert_eq!(Tz::Asia__Kolkata, actual.unwrap());
+        assert_eq!(
+            vec!["'IST' is ambiguous, using Asia/Kolkata (could also be Europe/Dublin)"],
+            warnings
+        );
+    }
+
+    #[test]
+    fn resolve_unknown() {
+        let actual = resolve_time_zone(&construct_aliases(), "Mars/Olympus", &mut vec![]);
+        assert!(actual.is_err());
+    }
+
+    #[test]
+    fn daylight_saving_time() {
+        let time = NaiveTime::from_hms_opt(14, 30, 0).unwrap();
+        let winter = NaiveDate::from_ymd_opt(2024, 1, 15);
+        let summer = NaiveDate::from_ymd_opt(2024, 7, 15);
+
+        let actual = convert_time(Tz::America__New_York, Tz::Asia__Tokyo, time, winter).unwrap();
+        assert_eq!(datetime("2024-01-16 04:30"), actual.time);
+        let actual = convert_time(Tz::America__New_York, Tz::Asia__Tokyo, time, summer).unwrap();
+        assert_eq!(datetime("2024-07-16 03:30"), actual.time);
+    }
+
+    #[test]
+    fn repeated_time() {
+        let time = NaiveTime::from_hms_opt(1, 30, 0).unwrap();
+        let date = NaiveDate::from_ymd_opt(2024, 11, 3);
+        let actual = convert_time(Tz::America__New_York, Tz::UTC, time, date).unwrap();
+        assert_eq!(datetime("2024-11-03 05:30"), actual.time);
+        assert_eq!(1, actual.warnings.len());
+    }
+
+    #[test]
+    fn skipped_time() {
+        let time = NaiveTime::from_hms_opt(2, 30, 0).unwrap();
+        let date = NaiveDate::from_ymd_opt(2024, 3, 10);
+        let actual = convert_time(Tz::America__New_York, Tz::UTC, time, date);
+        assert_eq!(
+            "2024-03-10 02:30:00 doesn't exist in America/New_York as the clocks go forward",
+            actual.unwrap_err().to_string()
+        );
+    }
+}
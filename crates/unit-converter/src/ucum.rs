@@ -0,0 +1,206 @@
+use log::{debug, warn};
+
+use crate::converter::{error::ConversionError, UnitConversion};
+use crate::parser::{parse_expression, ParsedExpression};
+
+#[derive(Debug, Clone)]
+pub struct UcumCode {
+    pub unit: String,
+    pub code: String,
+    pub unit_type: String,
+    /// Whether the code can take a prefix (i.e. "g" but not "d" for days or "[lb_av]").
+    pub metric: bool,
+}
+
+// Ordered so that multi-character prefixes are matched before single character prefixes that
+// they start with (i.e. "da" before "d").
+const PREFIXES: [(&str, f64); 24] = [
+    ("da", 1e1),
+    ("Ki", 1024.0),
+    ("Mi", 1048576.0),
+    ("Gi", 1073741824.0),
+    ("Ti", 1099511627776.0),
+    ("Y", 1e24),
+    ("Z", 1e21),
+    ("E", 1e18),
+    ("P", 1e15),
+    ("T", 1e12),
+    ("G", 1e9),
+    ("M", 1e6),
+    ("k", 1e3),
+    ("h", 1e2),
+    ("d", 1e-1),
+    ("c", 1e-2),
+    ("m", 1e-3),
+    ("u", 1e-6),
+    ("n", 1e-9),
+    ("p", 1e-12),
+    ("f", 1e-15),
+    ("a", 1e-18),
+    ("z", 1e-21),
+    ("y", 1e-24),
+];
+
+/// Parses a conversion expression where both units are given as UCUM codes. The returned scale
+/// is the prefix factor of the target unit, which the converted value needs to be divided by.
+pub fn parse_ucum_conversion(
+    codes: &[UcumCode],
+    input: &str,
+) -> Result<(UnitConversion, f64), ConversionError> {
+    let ParsedExpression {
+        value,
+        from: convert_from,
+        substance,
+        to: convert_to,
+        components,
+        date,
+        uncertainty,
+        significant_figures,
+    } = parse_expression(input)?;
+    if !components.is_empty() {
+        return Err(ConversionError::new(
+            "Splitting a result across several units isn't supported for UCUM codes",
+        ));
+    }
+
+    let (first_type, from, from_scale) = parse_ucum_unit(codes, convert_from)?;
+    let (second_type, to, to_scale) = parse_ucum_unit(codes, convert_to)?;
+    debug!(
+        "Parsed UCUM codes {} and {} to units {} (x{}) and {} (x{})",
+        convert_from, convert_to, from, from_scale, to, to_scale
+    );
+
+    if first_type != second_type && substance.is_none() {
+        return Err(ConversionError::new("Units are of different types"));
+    }
+    let to_type = (second_type != first_type).then_some(second_type);
+
+    let conversion = UnitConversion {
+        value: value * from_scale,
+        from,
+        to,
+        unit_type: first_type,
+        to_type,
+        to_ucum: Some(convert_to.to_owned()),
+        substance: substance.map(|s| s.to_owned()),
+        date,
+        uncertainty: uncertainty.map(|uncertainty| uncertainty * from_scale),
+        significant_figures,
+        ..Default::default()
+    };
+    Ok((conversion, to_scale))
+}
+
+/// Finds the unit for a UCUM code. Codes that aren't mapped directly are split into a prefix
+/// and an atom, with the prefix returned as a scale. Only metric atoms can be prefixed.
+pub fn parse_ucum_unit(
+    codes: &[UcumCode],
+    input: &str,
+) -> Result<(String, String, f64), ConversionError> {
+    if let Some(code) = codes.iter().find(|c| c.code == input) {
+        return Ok((code.unit_type.to_owned(), code.unit.to_owned(), 1.0));
+    }
+
+    for (prefix, scale) in PREFIXES {
+        let atom = match input.strip_prefix(prefix) {
+            Some(atom) if !atom.is_empty() => atom,
+            _ => continue,
+        };
+        if let Some(code) = codes.iter().find(|c| c.code == atom && c.metric) {
+            return Ok((code.unit_type.to_owned(), code.unit.to_owned(), scale));
+        }
+    }
+
+    warn!("Error parsing {} into a valid UCUM unit", input);
+    Err(ConversionError::new(&format!(
+        "'{}' is not a valid UCUM code",
+        input
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn construct_ucum_codes() -> Vec<UcumCode> {
+        let codes = [
+            ("Grams", "g", "Weight"),
+            ("Kilograms", "kg", "Weight"),
+            ("Pounds", "[lb_av]", "Weight"),
+            ("Liters", "L", "Capacity"),
+            ("MilligramsPerDeciliter", "mg/dL", "Concentration"),
+            ("Days", "d", "Time"),
+        ];
+        codes
+            .iter()
+            .map(|(unit, code, unit_type)| UcumCode {
+                unit: unit.to_string(),
+                code: code.to_string(),
+                unit_type: unit_type.to_string(),
+                metric: *unit_type != "Time" && !code.starts_with('['),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn mapped_code() {
+        let codes = construct_ucum_codes();
+        let actual = parse_ucum_unit(&codes, "[lb_av]").unwrap();
+        assert_eq!(
+            (String::from("Weight"), String::from("Pounds"), 1.0),
+            actual
+        );
+    }
+
+    #[test]
+    fn mapped_code_takes_precedence_over_prefix() {
+        let codes = construct_ucum_codes();
+        let actual = parse_ucum_unit(&codes, "kg").unwrap();
+        assert_eq!(
+            (String::from("Weight"), String::from("Kilograms"), 1.0),
+            actual
+        );
+
+        let actual = parse_ucum_unit(&codes, "mg/dL").unwrap();
+        assert_eq!(String::from("MilligramsPerDeciliter"), actual.1);
+    }
+
+    #[test]
+    fn prefixed_code() {
+        let codes = construct_ucum_codes();
+        let actual = parse_ucum_unit(&codes, "dL").unwrap();
+        assert_eq!(
+            (String::from("Capacity"), String::from("Liters"), 1e-1),
+            actual
+        );
+
+        let actual = parse_ucum_unit(&codes, "dag").unwrap();
+        assert_eq!((String::from("Weight"), String::from("Grams"), 1e1), actual);
+    }
+
+    #[test]
+    fn prefixed_non_metric_code() {
+        let codes = construct_ucum_codes();
+        assert!(parse_ucum_unit(&codes, "k[lb_av]").is_err());
+        assert!(parse_ucum_unit(&codes, "cd").is_err());
+        assert!(parse_ucum_unit(&codes, "kd").is_err());
+    }
+
+    #[test]
+    fn conversion() {
+        let codes = construct_ucum_codes();
+        let (actual, scale) = parse_ucum_conversion(&codes, "10 mg -> [lb_av]").unwrap();
+
+        let expected = UnitConversion {
+            value: 10.0 * 1e-3,
+            from: String::from("Grams"),
+            to: String::from("Pounds"),
+            unit_type: String::from("Weight"),
+            to_ucum: Some(String::from("[lb_av]")),
+            significant_figures: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(expected, actual);
+        assert_eq!(1.0, scale);
+    }
+}
@@ -0,0 +1,126 @@
+use crate::converter::error::ConversionError;
+
+/// Propagates an uncertainty through a conversion by linearizing it around the value, using a
+/// central difference to approximate the derivative. A one-sided difference is used at the edge
+/// of the conversion's range (i.e. the first and last points of a table).
+pub fn linearize(
+    mut convert: impl FnMut(f64) -> Result<f64, ConversionError>,
+    value: f64,
+    uncertainty: f64,
+) -> Result<f64, ConversionError> {
+    if uncertainty == 0.0 {
+        return Ok(0.0);
+    }
+
+    // The step is relative to the value so that it doesn't cross zero, where conversions such
+    // as logarithms aren't defined.
+    let step = match value == 0.0 {
+        true => 1e-9,
+        false => value.abs() * 1e-6,
+    };
+    let derivative = match (convert(value - step), convert(value + step)) {
+        (Ok(lower), Ok(upper)) => (upper - lower) / (2.0 * step),
+        (Err(_), Ok(upper)) => (upper - convert(value)?) / step,
+        (Ok(lower), Err(_)) => (convert(value)? - lower) / step,
+        (Err(err), Err(_)) => return Err(err),
+    };
+    Ok((derivative * uncertainty).abs())
+}
+
+/// Propagates an uncertainty through a conversion that isn't continuous (i.e. a step table), by
+/// converting both ends of the interval and taking the larger distance from the result. Ends
+/// outside the conversion's range are limited to the value itself.
+pub fn propagate_interval(
+    mut convert: impl FnMut(f64) -> Result<f64, ConversionError>,
+    value: f64,
+    uncertainty: f64,
+) -> Result<f64, ConversionError> {
+    let result = convert(value)?;
+    let lower = convert(value - uncertainty).unwrap_or(result);
+    let upper = convert(value + uncertainty).unwrap_or(result);
+    Ok((lower - result).abs().max((upper - result).abs()))
+}
+
+/// Formats a value with its uncertainty (i.e. "4.92 ± 0.08"). The uncertainty is rounded to one
+/// significant figure, or two if it starts with a 1, and the value to the same decimal place.
+pub fn format_uncertainty(value: f64, uncertainty: f64) -> String {
+    let Some(place) = uncertainty_place(uncertainty) else {
+        return format!("{} ± 0", value);
+    };
+
+    let round = |x: f64| (x / 10f64.powi(place)).round() * 10f64.powi(place);
+    let decimals = (-place).max(0) as usize;
+    format!(
+        "{:.*} ± {:.*}",
+        decimals,
+        round(value),
+        decimals,
+        round(uncertainty)
+    )
+}
+
+/// The power of 10 of the last figure an uncertainty is rounded to, which keeps one significant
+/// figure, or two if it starts with a 1 (i.e. -2 for 0.08 and 0.14). Returns None for an
+/// uncertainty of zero.
+pub fn uncertainty_place(uncertainty: f64) -> Option<i32> {
+    if uncertainty <= 0.0 || !uncertainty.is_finite() {
+        return None;
+    }
+
+    let magnitude = uncertainty.log10().floor() as i32;
+    let leading = (uncertainty / 10f64.powi(magnitude)).floor();
+    let figures = if leading == 1.0 { 2 } else { 1 };
+    Some(magnitude - figures + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn linearize_linear() {
+        let actual = linearize(|x| Ok(x / 2.54), 12.5, 0.2).unwrap();
+        assert!((actual - 0.2 / 2.54).abs() < 1e-9);
+    }
+
+    #[test]
+    fn linearize_nonlinear() {
+        let actual = linearize(|x| Ok(x * x), 3.0, 0.1).unwrap();
+        assert!((actual - 0.6).abs() < 1e-6);
+        assert_eq!(0.0, linearize(|x| Ok(x * x), 3.0, 0.0).unwrap());
+    }
+
+    #[test]
+    fn linearize_at_range_edge() {
+        let convert = |x: f64| match (0.0..=1.0).contains(&x) {
+            true => Ok(3.0 * x),
+            false => Err(ConversionError::new("Out of range")),
+        };
+        assert!((linearize(convert, 1.0, 0.1).unwrap() - 0.3).abs() < 1e-6);
+        assert!((linearize(convert, 0.0, 0.1).unwrap() - 0.3).abs() < 1e-6);
+        assert!(linearize(convert, 2.0, 0.1).is_err());
+    }
+
+    #[test]
+    fn interval() {
+        let convert = |x: f64| match x >= 0.0 {
+            true => Ok(x.floor()),
+            false => Err(ConversionError::new("Out of range")),
+        };
+        assert_eq!(0.0, propagate_interval(convert, 2.5, 0.4).unwrap());
+        assert_eq!(1.0, propagate_interval(convert, 2.5, 0.6).unwrap());
+        assert_eq!(1.0, propagate_interval(convert, 0.5, 0.6).unwrap());
+    }
+
+    #[test]
+    fn format() {
+        assert_eq!(
+            "4.92 ± 0.08",
+            format_uncertainty(4.921259842519685, 0.07874015748031496)
+        );
+        assert_eq!("12.5 ± 0.2", format_uncertainty(12.5, 0.2));
+        assert_eq!("1.23 ± 0.14", format_uncertainty(1.2345, 0.14));
+        assert_eq!("1230 ± 60", format_uncertainty(1234.0, 56.0));
+        assert_eq!("5 ± 0", format_uncertainty(5.0, 0.0));
+    }
+}
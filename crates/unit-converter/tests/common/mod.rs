@@ -1,13 +1,57 @@
 use simple_logger::SimpleLogger;
 use unitconvert::converter::builder::UnitConverterBuilder;
-use unitconvert::converter::UnitConverter;
+use unitconvert::converter::{ParseMode, UnitConverter};
+use unitconvert::source::csv::currency::ExchangeRateSourceCsv;
 use unitconvert::source::toml::conversions::BaseConversionsSourceToml;
+use unitconvert::source::toml::substances::SubstanceDefinitionSourceToml;
+use unitconvert::source::toml::timezones::TimeZoneAliasSourceToml;
+use unitconvert::source::toml::ucum::UcumDefinitionSourceToml;
 use unitconvert::source::toml::units::UnitDefinitionSourceToml;
 
 pub fn setup() -> UnitConverter {
+    setup_builder().build().unwrap()
+}
+
+/// Creates a builder with the default definitions, for tests that need to configure the converter.
+pub fn setup_builder() -> UnitConverterBuilder {
+    let unit_definitions_path = concat!(env!("CARGO_MANIFEST_DIR"), "/../../Units.toml");
+    let default_converions_path =
+        concat!(env!("CARGO_MANIFEST_DIR"), "/../../Base_Conversions.toml");
+    let substances_path = concat!(env!("CARGO_MANIFEST_DIR"), "/../../Substances.toml");
+    let exchange_rates_path = concat!(env!("CARGO_MANIFEST_DIR"), "/../../Exchange_Rates.csv");
+    let time_zones_path = concat!(env!("CARGO_MANIFEST_DIR"), "/../../Time_Zones.toml");
+
+    let conversions = BaseConversionsSourceToml::new(default_converions_path)
+        .load()
+        .unwrap();
+    let units = UnitDefinitionSourceToml::new(unit_definitions_path)
+        .load()
+        .unwrap();
+    let substances = SubstanceDefinitionSourceToml::new(substances_path)
+        .load()
+        .unwrap();
+    let exchange_rates = ExchangeRateSourceCsv::new(exchange_rates_path)
+        .load()
+        .unwrap();
+    let time_zone_aliases = TimeZoneAliasSourceToml::new(time_zones_path)
+        .load()
+        .unwrap();
+
+    UnitConverterBuilder::new()
+        .reverse_base_conversions(true)
+        .cache_results(true)
+        .add_unit_definitions(units)
+        .add_base_conversions(conversions)
+        .add_substances(substances)
+        .add_exchange_rates(exchange_rates)
+        .add_time_zone_aliases(time_zone_aliases)
+}
+
+pub fn setup_ucum() -> UnitConverter {
     let unit_definitions_path = concat!(env!("CARGO_MANIFEST_DIR"), "/../../Units.toml");
     let default_converions_path =
         concat!(env!("CARGO_MANIFEST_DIR"), "/../../Base_Conversions.toml");
+    let ucum_path = concat!(env!("CARGO_MANIFEST_DIR"), "/../../Ucum.toml");
 
     let conversions = BaseConversionsSourceToml::new(default_converions_path)
         .load()
@@ -15,12 +59,15 @@ pub fn setup() -> UnitConverter {
     let units = UnitDefinitionSourceToml::new(unit_definitions_path)
         .load()
         .unwrap();
+    let codes = UcumDefinitionSourceToml::new(ucum_path).load().unwrap();
 
     UnitConverterBuilder::new()
         .reverse_base_conversions(true)
         .cache_results(true)
+        .parse_mode(ParseMode::Ucum)
         .add_unit_definitions(units)
         .add_base_conversions(conversions)
+        .add_ucum_codes(codes)
         .build()
         .unwrap()
 }
@@ -1,5 +1,15 @@
-use self::common::{setup, setup_test_logger};
+use std::f64::consts;
+
+use self::common::{setup, setup_builder, setup_test_logger, setup_ucum};
+use chrono::{Local, NaiveDateTime, TimeDelta, Timelike};
+use expr::expression::{ExpressionContext, InMemoryExpressionContext};
 use test_case::test_case;
+use unitconvert::converter::builder::UnitConverterBuilder;
+use unitconvert::converter::{CaseSensitivity, ConversionValue, SignificantFigures};
+use unitconvert::format::{Notation, ResultFormatter, UnitStyle};
+use unitconvert::source::csv::currency::ExchangeRateSourceCsv;
+use unitconvert::source::toml::conversions::BaseConversionsSourceToml;
+use unitconvert::source::toml::units::UnitDefinitionSourceToml;
 
 mod common;
 
@@ -13,6 +23,7 @@ mod common;
 #[test_case("453406564nm -> km", 0.0004534065640000001 ; "nanometers to kilometers")]
 #[test_case("87pm -> nm",        0.087000000000000001  ; "picometers to nanometers")]
 #[test_case("1ly -> km",         9460730472580.0       ; "light-years to kilometers")]
+#[allow(clippy::excessive_precision)]
 pub fn length_conversion(input: &str, expected: f64) {
     setup_test_logger();
     let mut converter = setup();
@@ -72,3 +83,903 @@ pub fn temperature_conversion(input: &str, expected: f64) {
     assert!(actual.is_ok(), "Returned error {:?}", actual.err());
     assert_eq!(expected, actual.unwrap().value);
 }
+
+#[test_case("10 [lb_av] -> kg", 4.535923700000001, "kg"      ; "pounds to kilograms")]
+#[test_case("2 kg -> [lb_av]",  4.409245243697551, "[lb_av]" ; "kilograms to pounds")]
+#[test_case("1 [in_i] -> cm",   2.54,              "cm"      ; "inches to centimeters")]
+#[test_case("250 mg/dL -> g/L", 2.5,               "g/L"     ; "concentration")]
+#[test_case("3 L -> dL",        30.0,              "dL"      ; "prefixed target unit")]
+#[test_case("20 Cel -> [degF]", 68.0,              "[degF]"  ; "celsius to fahrenheit")]
+pub fn ucum_conversion(input: &str, expected: f64, expected_code: &str) {
+    let mut converter = setup_ucum();
+    let actual = converter.convert_from_expression(input);
+
+    assert!(actual.is_ok(), "Returned error {:?}", actual.err());
+    let actual = actual.unwrap();
+    assert_eq!(expected, actual.value);
+    assert_eq!(Some(expected_code.to_string()), actual.to_ucum);
+}
+
+#[test_case("1 Pa -> a"  ; "peta years")]
+#[test_case("1 ka -> d"  ; "kilo years")]
+#[test_case("1 cd -> h"  ; "centi days")]
+#[test_case("1 mh -> s"  ; "milli hours")]
+pub fn ucum_prefixed_non_metric(input: &str) {
+    let mut converter = setup_ucum();
+    let actual = converter.convert_from_expression(input);
+
+    let code = input[2..].split_once(' ').unwrap().0;
+    assert_eq!(
+        format!("'{}' is not a valid UCUM code", code),
+        actual.unwrap_err().to_string()
+    );
+}
+
+#[test_case("30 mpg -> L/100km",     7.84048611         ; "miles per gallon to liters per 100 kilometers")]
+#[test_case("7.84 L/100km -> mpg",   30.001860114795918 ; "liters per 100 kilometers to miles per gallon")]
+#[test_case("10 km/L -> L/100km",    10.0               ; "kilometers per liter to liters per 100 kilometers")]
+#[test_case("5 L/100km -> km/L",     20.0               ; "liters per 100 kilometers to kilometers per liter")]
+#[test_case("20 kmpl -> mpg",        47.042916659999996 ; "kilometers per liter to miles per gallon")]
+pub fn fuel_economy_conversion(input: &str, expected: f64) {
+    let mut converter = setup();
+
+    // Run each test case twice to ensure that any caching doesn't alter the result.
+    for _ in 0..2 {
+        let actual = converter.convert_from_expression(input);
+
+        assert!(actual.is_ok(), "Returned error {:?}", actual.err());
+        assert_eq!(expected, actual.unwrap().value);
+    }
+}
+
+#[test_case("0 mpg -> L/100km"  ; "miles per gallon to liters per 100 kilometers")]
+#[test_case("0 L/100km -> mpg"  ; "liters per 100 kilometers to miles per gallon")]
+pub fn fuel_economy_conversion_zero(input: &str) {
+    for mut converter in [
+        setup(),
+        setup_builder().exact_arithmetic(true).build().unwrap(),
+    ] {
+        let actual = converter.convert_from_expression(input);
+        assert_eq!(
+            "Cannot convert zero through a reciprocal conversion",
+            actual.unwrap_err().to_string()
+        );
+    }
+}
+
+#[test_case("10 AWG -> mm",          2.5882             ; "wire gauge to millimeters")]
+#[test_case("10.5 AWG -> mm",        2.4465000000000003 ; "half wire gauge to millimeters")]
+#[test_case("12 AWG -> in",          0.08080708661417324 ; "wire gauge to inches")]
+#[test_case("2.5882 mm -> AWG",      10.0               ; "millimeters to wire gauge")]
+#[test_case("5 Bft -> m/s",          8.0                ; "beaufort to meters per second")]
+#[test_case("20 kn -> Bft",          5.0                ; "knots to beaufort")]
+#[test_case("50 mph -> Bft",         9.0                ; "miles per hour to beaufort")]
+#[test_case("40 m/s -> Bft",         12.0               ; "wind speed above beaufort scale")]
+pub fn lookup_table_conversion(input: &str, expected: f64) {
+    let mut converter = setup();
+
+    // Run each test case twice to ensure that any caching doesn't alter the result.
+    for _ in 0..2 {
+        let actual = converter.convert_from_expression(input);
+
+        assert!(actual.is_ok(), "Returned error {:?}", actual.err());
+        assert_eq!(expected, actual.unwrap().value);
+    }
+}
+
+#[test_case("50 AWG -> mm"           ; "wire gauge above table")]
+#[test_case("20 mm -> AWG"           ; "millimeters above table")]
+#[test_case("-1 m/s -> Bft"          ; "wind speed below beaufort scale")]
+pub fn lookup_table_out_of_range(input: &str) {
+    let mut converter = setup();
+    let actual = converter.convert_from_expression(input);
+
+    assert!(actual.is_err());
+    assert!(actual
+        .unwrap_err()
+        .to_string()
+        .contains("outside the range of the lookup table"));
+}
+
+#[test_case("30 dBm -> W",           1.0                ; "decibel milliwatts to watts")]
+#[test_case("1 mW -> dBm",           0.0                ; "milliwatts to decibel milliwatts")]
+#[test_case("2 W -> dBm",            33.010299956639805 ; "watts to decibel milliwatts")]
+#[test_case("0 dBW -> dBm",          29.999999999999996 ; "decibel watts to decibel milliwatts")]
+#[test_case("20 dBV -> V",           10.0               ; "decibel volts to volts")]
+#[test_case("0 dBu -> dBV",          -2.218487496163563 ; "decibel unloaded to decibel volts")]
+#[test_case("1 Pa -> dBSPL",         93.97940008672037  ; "pascals to sound pressure level")]
+#[test_case("7 pH -> mol/L",         1e-7               ; "ph to moles per liter")]
+#[test_case("0.001 mol/L -> pH",     2.9999999999999996 ; "moles per liter to ph")]
+#[test_case("1 Np -> A/A0",          2.7182818284590446 ; "nepers to amplitude ratio")]
+#[test_case("100 P/P0 -> dB",        20.0               ; "power ratio to decibels")]
+#[test_case("5 mag -> F/F0",          0.01               ; "magnitudes to flux ratio")]
+#[test_case("100 F/F0 -> mag",        -5.0               ; "flux ratio to magnitudes")]
+pub fn logarithmic_conversion(input: &str, expected: f64) {
+    let mut converter = setup();
+
+    // Run each test case twice to ensure that any caching doesn't alter the result.
+    for _ in 0..2 {
+        let actual = converter.convert_from_expression(input);
+
+        assert!(actual.is_ok(), "Returned error {:?}", actual.err());
+        assert_eq!(expected, actual.unwrap().value);
+    }
+}
+
+#[test]
+pub fn logarithmic_non_positive_value() {
+    let mut converter = setup();
+    let actual = converter.convert_from_expression("0 mW -> dBm");
+
+    assert!(actual.is_err());
+}
+
+#[test]
+pub fn sum_logarithmic_levels() {
+    let converter = setup();
+    let actual = converter
+        .sum_levels("Power", "DecibelMilliwatts", &[30.0, 30.0])
+        .unwrap();
+
+    assert_eq!(33.010299956639805, actual);
+    assert!(converter.sum_levels("Power", "Watts", &[1.0]).is_err());
+}
+
+#[test_case("12 pt -> px",           16.0               ; "points to pixels")]
+#[test_case("16 px -> pt",           12.0               ; "pixels to points")]
+#[test_case("2 pc -> pt",            24.0               ; "picas to points")]
+#[test_case("2em -> px",             32.0               ; "ems to pixels")]
+#[test_case("24 px -> rem",          1.5                ; "pixels to root ems")]
+#[test_case("1 in -> px",            96.0               ; "inches to pixels")]
+#[test_case("1 em -> pt",            12.0               ; "ems to points")]
+#[test_case("25.4 mm -> pc",         5.999999999999999  ; "millimeters to picas")]
+#[test_case("1 in -> pt",            72.0               ; "inches to points")]
+pub fn parameterized_conversion_defaults(input: &str, expected: f64) {
+    let mut converter = setup();
+    let actual = converter.convert_from_expression(input);
+
+    assert!(actual.is_ok(), "Returned error {:?}", actual.err());
+    assert_eq!(expected, actual.unwrap().value);
+}
+
+#[test]
+pub fn parameterized_conversion_per_converter() {
+    let mut converter = setup();
+    converter.set_parameter("dpi", 300.0);
+
+    let actual = converter.convert_from_expression("12 pt -> px").unwrap();
+    assert_eq!(50.0, actual.value);
+}
+
+#[test]
+pub fn parameterized_conversion_font_size() {
+    let mut converter = setup();
+    converter.set_parameter("font_size", 20.0);
+
+    let actual = converter.convert_from_expression("2 em -> px").unwrap();
+    assert_eq!(40.0, actual.value);
+    let actual = converter.convert_from_expression("2 rem -> px").unwrap();
+    assert_eq!(32.0, actual.value);
+
+    let mut ctx = InMemoryExpressionContext::default();
+    ctx.var("root_font_size", 10.0);
+    let actual = converter
+        .convert_from_expression_with_ctx("2 rem -> em", &ctx)
+        .unwrap();
+    assert_eq!(1.0, actual.value);
+}
+
+#[test]
+pub fn parameterized_conversion_per_call() {
+    let mut converter = setup();
+    converter.set_parameter("dpi", 300.0);
+    let mut ctx = InMemoryExpressionContext::default();
+    ctx.var("dpi", 72.0);
+
+    let actual = converter
+        .convert_from_expression_with_ctx("12 pt -> px", &ctx)
+        .unwrap();
+    assert_eq!(12.0, actual.value);
+
+    // Parameters given for a single call don't change the converter.
+    let actual = converter.convert_from_expression("12 pt -> px").unwrap();
+    assert_eq!(50.0, actual.value);
+}
+
+#[test]
+pub fn parameterized_conversion_missing_parameter() {
+    let mut converter = UnitConverterBuilder::new()
+        .add_unit_definitions(
+            UnitDefinitionSourceToml::from_contents(
+                "units",
+                "[Typography]\nEms = ['em']\nPixels = ['px']\n",
+            )
+            .load()
+            .unwrap(),
+        )
+        .add_base_conversions(
+            BaseConversionsSourceToml::from_contents(
+                "conversions",
+                "[Typography]\nEms = { Pixels = \"em * font_size\" }\n",
+            )
+            .load()
+            .unwrap(),
+        )
+        .build()
+        .unwrap();
+
+    let actual = converter.convert_from_expression("2 em -> px");
+    assert!(actual.is_err());
+    assert_eq!(
+        "Missing parameter 'font_size' for conversion from Ems to Pixels",
+        actual.unwrap_err().to_string()
+    );
+
+    converter.set_parameter("font_size", 16.0);
+    let actual = converter.convert_from_expression("2 em -> px").unwrap();
+    assert_eq!(32.0, actual.value);
+}
+
+#[test_case("2 cups of flour -> g",         280.593648489      ; "cups of flour to grams")]
+#[test_case("500 kg water -> m³",           0.5                ; "kilograms of water to cubic meters")]
+#[test_case("1 m3 of steel -> t",           7.8500000000000005 ; "cubic meters of steel to tonnes")]
+#[test_case("100 g of plain flour -> cups", 0.7127745088921376 ; "grams of flour by alias to cups")]
+#[test_case("1 tbsp honey -> g",            20.997205989375    ; "tablespoons of honey to grams")]
+#[test_case("3 cups of water -> ml",        709.7647095        ; "substance within a single type")]
+pub fn substance_conversion(input: &str, expected: f64) {
+    let mut converter = setup();
+    let actual = converter.convert_from_expression(input);
+
+    assert!(actual.is_ok(), "Returned error {:?}", actual.err());
+    assert_eq!(expected, actual.unwrap().value);
+}
+
+#[test]
+pub fn substance_conversion_along_approximate_path() {
+    let mut converter = setup_builder()
+        .add_unit_definitions(
+            UnitDefinitionSourceToml::from_contents("units", "[Capacity]\nJugs = ['jug']\n")
+                .load()
+                .unwrap(),
+        )
+        .add_base_conversions(
+            BaseConversionsSourceToml::from_contents(
+                "conversions",
+                "[Capacity]\nJugs = { Liters = { approx = 1.14 } }\n",
+            )
+            .load()
+            .unwrap(),
+        )
+        .significant_figures(SignificantFigures::Fixed(6))
+        .build()
+        .unwrap();
+
+    let actual = converter
+        .convert_from_expression("2 jug of water -> kg")
+        .unwrap();
+    assert_eq!(2.28, actual.value);
+    assert_eq!(Some(3), actual.significant_figures);
+    assert!((0.005 - actual.relative_error).abs() < 1e-18);
+}
+
+#[test_case("2 cups of unobtainium -> g", "Unknown substance 'unobtainium'"               ; "unknown substance")]
+#[test_case("2 cups of flour -> km",      "Unable to convert between Capacity and Length" ; "no bridge")]
+#[test_case("2 cups -> g",                "Units are of different types"                  ; "no substance")]
+#[test_case("5 m of garbage text -> ft",  "Unknown substance 'garbage text'"              ; "unknown substance within a single type")]
+pub fn substance_conversion_error(input: &str, expected: &str) {
+    let mut converter = setup();
+    let actual = converter.convert_from_expression(input);
+
+    assert!(actual.is_err());
+    assert_eq!(expected, actual.unwrap_err().to_string());
+}
+
+#[test]
+pub fn substance_conversion_warning() {
+    let mut converter = setup();
+    let actual = converter
+        .convert_from_expression("3 cups of water -> ml")
+        .unwrap();
+    assert_eq!(
+        vec!["Substances are only used when converting between unit types"],
+        actual.warnings
+    );
+
+    let actual = converter
+        .convert_from_expression("2 cups of flour -> g")
+        .unwrap();
+    assert!(actual.warnings.is_empty());
+}
+
+#[test_case("100 EUR -> USD @ 2024-03-01",    108.42, "2024-03-01" ; "base currency")]
+#[test_case("108.42 USD -> EUR @ 2024-03-05", 100.0,  "2024-03-01" ; "to base currency")]
+#[test_case("100 EUR -> USD @ 2024-06-03",    108.71, "2024-06-03" ; "later rate")]
+#[test_case("85.56 GBP -> USD @ 2024-03-01",  108.42, "2024-03-01" ; "cross rate")]
+pub fn currency_conversion(input: &str, expected: f64, date: &str) {
+    let mut converter = setup();
+    let actual = converter.convert_from_expression(input).unwrap();
+
+    assert_eq!(expected, actual.value);
+    assert_eq!(Some(date.parse().unwrap()), actual.date);
+    assert!(actual.warnings.is_empty());
+}
+
+#[test_case("100 EUR -> USD @ 2024-05-01", "Exchange rate from 2024-03-01 is 61 days old" ; "historical")]
+#[test_case("1 km -> m @ 2024-03-01",      "Dates are only used when converting between currencies" ; "not currency")]
+pub fn currency_conversion_warning(input: &str, expected: &str) {
+    let mut converter = setup();
+    let actual = converter.convert_from_expression(input).unwrap();
+    assert_eq!(vec![expected.to_string()], actual.warnings);
+}
+
+#[test]
+pub fn currency_conversion_max_rate_age() {
+    let today = Local::now().date_naive();
+    let contents = format!("{},EUR,USD,1.1\n", today - TimeDelta::days(3));
+    let rates = ExchangeRateSourceCsv::from_contents("rates", &contents)
+        .load()
+        .unwrap();
+    let mut converter = UnitConverterBuilder::new()
+        .add_exchange_rates(rates)
+        .max_rate_age(TimeDelta::days(2))
+        .build()
+        .unwrap();
+
+    let actual = converter.convert_from_expression("10 EUR -> USD").unwrap();
+    assert_eq!(11.0, actual.value);
+    assert_eq!(Some(today - TimeDelta::days(3)), actual.date);
+    assert_eq!(
+        vec![
+            String::from("Exchange rate from ")
+                + &(today - TimeDelta::days(3)).to_string()
+                + " is 3 days old"
+        ],
+        actual.warnings
+    );
+}
+
+#[test]
+pub fn currency_conversion_no_rate() {
+    let mut converter = setup();
+    let actual = converter.convert_from_expression("100 EUR -> USD @ 2023-12-31");
+    assert_eq!(
+        "No exchange rate between EUR and USD on or before 2023-12-31",
+        actual.unwrap_err().to_string()
+    );
+}
+
+#[test_case("90 min -> h",         1.5                ; "minutes to hours")]
+#[test_case("1 wk -> s",           604800.0           ; "weeks to seconds")]
+#[test_case("1500 ms -> s",        1.5                ; "milliseconds to seconds")]
+#[test_case("1 yr -> d",           365.2425           ; "gregorian years to days")]
+#[test_case("1 yr_j -> d",         365.25             ; "julian years to days")]
+#[test_case("1 yr_360 -> d",       360.0              ; "commercial years to days")]
+#[test_case("1 mo -> d",           30.436875          ; "gregorian months to days")]
+#[test_case("2 mo_30 -> d",        60.0               ; "commercial months to days")]
+#[test_case("PT1H30M -> min",      90.0               ; "iso duration")]
+#[test_case("P1DT12H -> d",        1.5                ; "iso duration with days")]
+#[test_case("P1W -> d",            7.0                ; "iso duration in weeks")]
+pub fn duration_conversion(input: &str, expected: f64) {
+    let mut converter = setup();
+    let actual = converter.convert_from_expression(input).unwrap();
+    assert_eq!(expected, actual.value);
+}
+
+#[test_case("90061 s -> d h min s",  vec![1.0, 1.0, 1.0, 1.0] ; "seconds")]
+#[test_case("P1DT1H1M1S -> h min s", vec![25.0, 1.0, 1.0]     ; "iso duration")]
+#[test_case("2.5 h -> d h min",      vec![0.0, 2.0, 30.0]     ; "fractional hours")]
+#[test_case("2500 m -> km m",        vec![2.0, 500.0]         ; "length")]
+pub fn split_conversion(input: &str, expected: Vec<f64>) {
+    let mut converter = setup();
+    let actual = converter.convert_from_expression(input).unwrap();
+    let values: Vec<f64> = actual.components.iter().map(|c| c.value).collect();
+    assert_eq!(expected, values);
+}
+
+#[test]
+pub fn split_conversion_different_types() {
+    let mut converter = setup();
+    let actual = converter.convert_from_expression("90061 s -> d h km");
+    assert_eq!(
+        "Units are of different types",
+        actual.unwrap_err().to_string()
+    );
+}
+
+#[test_case("14:30 America/New_York -> Asia/Tokyo @ 2024-01-15", "2024-01-16 04:30" ; "standard time")]
+#[test_case("14:30 America/New_York -> Asia/Tokyo @ 2024-07-15", "2024-07-16 03:30" ; "daylight saving time")]
+#[test_case("09:00 PST -> UTC @ 2024-07-15",                     "2024-07-15 17:00" ; "fixed offset alias")]
+#[test_case("09:00 PT -> UTC @ 2024-07-15",                      "2024-07-15 16:00" ; "zone alias")]
+#[test_case("23:45:30 europe/london -> CET @ 2024-01-15",        "2024-01-16 00:45" ; "case insensitive name")]
+pub fn time_zone_conversion(input: &str, expected: &str) {
+    let mut converter = setup();
+    let actual = converter.convert_from_expression(input).unwrap();
+
+    let expected = NaiveDateTime::parse_from_str(expected, "%Y-%m-%d %H:%M").unwrap();
+    assert_eq!(
+        Some(expected),
+        actual.time.map(|t| t.with_second(0).unwrap())
+    );
+    assert!(actual.warnings.is_empty());
+}
+
+#[test]
+pub fn time_zone_conversion_ambiguous_alias() {
+    let mut converter = setup();
+    let actual = converter
+        .convert_from_expression("12:00 IST -> UTC @ 2024-01-15")
+        .unwrap();
+
+    assert_eq!("Asia/Kolkata", actual.from);
+    assert_eq!(6.5, actual.value);
+    assert_eq!(
+        vec!["'IST' is ambiguous, using Asia/Kolkata (could also be Etc/GMT-1, Etc/GMT-2)"],
+        actual.warnings
+    );
+}
+
+// Abbreviations for summer or standard time keep their offset on any date, rather than following
+// the daylight saving time of a zone that uses them.
+#[test_case("14:30 BST -> UTC @ 2024-01-15", "2024-01-15 13:30" ; "british summer time in winter")]
+#[test_case("14:30 BST -> UTC @ 2024-07-15", "2024-07-15 13:30" ; "british summer time in summer")]
+#[test_case("14:30 AST -> UTC @ 2024-07-15", "2024-07-15 18:30" ; "atlantic standard time in summer")]
+pub fn time_zone_conversion_fixed_offset(input: &str, expected: &str) {
+    let mut converter = setup();
+    let actual = converter.convert_from_expression(input).unwrap();
+
+    let expected = NaiveDateTime::parse_from_str(expected, "%Y-%m-%d %H:%M").unwrap();
+    assert_eq!(
+        Some(expected),
+        actual.time.map(|t| t.with_second(0).unwrap())
+    );
+}
+
+#[test_case("14:30 Mars/Olympus -> UTC",                    "'Mars/Olympus' is not a valid time zone" ; "unknown time zone")]
+#[test_case("02:30 America/New_York -> UTC @ 2024-03-10",  "2024-03-10 02:30:00 doesn't exist in America/New_York as the clocks go forward" ; "skipped time")]
+pub fn time_zone_conversion_error(input: &str, expected: &str) {
+    let mut converter = setup();
+    let actual = converter.convert_from_expression(input);
+    assert_eq!(expected, actual.unwrap_err().to_string());
+}
+
+#[test_case("1 B -> bit",           8.0                ; "bytes to bits")]
+#[test_case("1 KiB -> B",           1024.0             ; "kibibytes to bytes")]
+#[test_case("1 GB -> MB",           1000.0             ; "gigabytes to megabytes")]
+#[test_case("1 GiB -> MiB",         1024.0             ; "gibibytes to mebibytes")]
+#[test_case("1 TB -> GiB",          931.3225746154785  ; "terabytes to gibibytes")]
+#[test_case("1 Mb -> MB",           0.125              ; "megabits to megabytes")]
+#[test_case("100 Mbit/s -> MiB/s",  11.920928955078125 ; "megabits to mebibytes per second")]
+#[test_case("1 Gbps -> MB/s",       125.0              ; "gigabits to megabytes per second")]
+pub fn data_conversion(input: &str, expected: f64) {
+    let mut converter = setup();
+    let actual = converter.convert_from_expression(input).unwrap();
+    assert_eq!(expected, actual.value);
+}
+
+#[test]
+pub fn data_conversion_is_case_sensitive() {
+    let mut converter = setup();
+    let bits = converter.convert_from_expression("8 Mb -> kB").unwrap();
+    let bytes = converter.convert_from_expression("8 MB -> kB").unwrap();
+    assert_eq!(1000.0, bits.value);
+    assert_eq!(8000.0, bytes.value);
+    assert!(converter.convert_from_expression("8 mB -> kB").is_err());
+}
+
+#[test_case("2 GB -> MiB",     Some("Gigabytes are decimal units of 1000, use Gibibytes (GiB) if binary units of 1024 were intended") ; "decimal")]
+#[test_case("2 GiB -> Mbit",   None                                                                                                   ; "binary")]
+#[test_case("2 MB/s -> Mbps",  Some("MegabytesPerSecond are decimal units of 1000, use MebibytesPerSecond (MiB/s) if binary units of 1024 were intended") ; "rate")]
+pub fn data_conversion_ambiguous_warning(input: &str, expected: Option<&str>) {
+    let unit_definitions_path = concat!(env!("CARGO_MANIFEST_DIR"), "/../../Units.toml");
+    let default_converions_path =
+        concat!(env!("CARGO_MANIFEST_DIR"), "/../../Base_Conversions.toml");
+    let conversions = BaseConversionsSourceToml::new(default_converions_path)
+        .load()
+        .unwrap();
+    let units = UnitDefinitionSourceToml::new(unit_definitions_path)
+        .load()
+        .unwrap();
+    let mut converter = UnitConverterBuilder::new()
+        .reverse_base_conversions(true)
+        .warn_ambiguous_units(true)
+        .add_unit_definitions(units)
+        .add_base_conversions(conversions)
+        .build()
+        .unwrap();
+
+    let actual = converter.convert_from_expression(input).unwrap();
+    let expected: Vec<String> = expected.into_iter().map(String::from).collect();
+    assert_eq!(expected, actual.warnings);
+}
+
+#[test_case("255 dec -> hex",                ConversionValue::Text(String::from("FF"))      ; "decimal to hexadecimal")]
+#[test_case("0b1011 -> dec",                 ConversionValue::Integer(11)                   ; "binary prefix to decimal")]
+#[test_case("0x1F -> bin",                   ConversionValue::Text(String::from("11111"))   ; "hexadecimal prefix to binary")]
+#[test_case("ff hex -> oct",                 ConversionValue::Text(String::from("377"))     ; "hexadecimal to octal")]
+#[test_case("MCMXCIV -> dec",                ConversionValue::Integer(1994)                 ; "roman to decimal")]
+#[test_case("2024 to roman",                 ConversionValue::Text(String::from("MMXXIV"))  ; "decimal to roman")]
+#[test_case("-42 dec -> hex",                ConversionValue::Text(String::from("-2A"))     ; "negative")]
+#[test_case("0xFFFFFFFFFFFFFFFFFF -> dec",   ConversionValue::Integer(4722366482869645213695) ; "larger than f64")]
+pub fn numeral_conversion(input: &str, expected: ConversionValue) {
+    let mut converter = setup();
+    let actual = converter.convert_from_expression(input).unwrap();
+    assert_eq!(Some(expected), actual.exact);
+    assert_eq!("Numeral", actual.unit_type);
+}
+
+#[test_case("102 bin -> dec",  "'102' is not a valid binary number"                       ; "invalid digits")]
+#[test_case("IIII -> dec",     "'IIII' is not a valid roman number"                        ; "non-standard roman numeral")]
+#[test_case("0 -> roman",      "Roman numerals can only represent values from 1 to 3999" ; "out of range")]
+#[test_case("12 km -> hex",    "'km' is not a valid numeral system"                      ; "not a numeral system")]
+pub fn numeral_conversion_error(input: &str, expected: &str) {
+    let mut converter = setup();
+    let actual = converter.convert_from_expression(input);
+    assert_eq!(expected, actual.unwrap_err().to_string());
+}
+
+#[test_case("180 deg -> rad",          consts::PI         ; "degrees to radians")]
+#[test_case("1 turn -> grad",          400.0              ; "turns to gradians")]
+#[test_case("90 deg -> mil",           1600.0             ; "degrees to mils")]
+#[test_case("1 rad -> mrad",           1000.0             ; "radians to milliradians")]
+#[test_case("1 deg -> arcsec",         3600.0             ; "degrees to arcseconds")]
+#[test_case("45°30'15\" -> deg",      45.50416666666667  ; "dms symbols")]
+#[test_case("45 30 15 S -> deg",       -45.50416666666667 ; "dms with hemisphere")]
+#[test_case("90° -> rad",              consts::FRAC_PI_2  ; "degree symbol")]
+pub fn angle_conversion(input: &str, expected: f64) {
+    let mut converter = setup();
+    let actual = converter.convert_from_expression(input).unwrap();
+    assert_eq!(expected, actual.value);
+}
+
+#[test_case("45.50416666666667 deg -> dms",    "45°30'15\""        ; "degrees")]
+#[test_case("1 rad -> dms",                    "57°17'44.806247\"" ; "radians")]
+#[test_case("-122.5 deg -> deg arcmin arcsec", "-122°30'0\""       ; "explicit units")]
+pub fn angle_conversion_dms(input: &str, expected: &str) {
+    let mut converter = setup();
+    let actual = converter.convert_from_expression(input).unwrap();
+    assert_eq!(Some(expected.to_owned()), actual.dms);
+    assert_eq!(None, actual.exact);
+}
+
+#[test]
+pub fn angle_conversion_dms_uncertainty() {
+    let mut converter = setup();
+    let actual = converter
+        .convert_from_expression("45.5 ± 0.1 deg -> dms")
+        .unwrap();
+    assert_eq!(Some(String::from("45°30'0\"")), actual.dms);
+    assert_eq!(
+        Some(String::from("45.50 ± 0.10")),
+        actual.formatted_uncertainty
+    );
+
+    let actual = converter
+        .convert_from_expression("45°30'15\" ± 0.1 -> dms")
+        .unwrap();
+    assert_eq!(Some(String::from("45°30'15\"")), actual.dms);
+    assert_eq!(Some(0.1), actual.uncertainty);
+}
+
+#[test_case("1 gal -> l",               3.785411784          ; "gallons in default region")]
+#[test_case("1 gal_imp -> l",           4.54609              ; "qualified imperial gallons")]
+#[test_case("1 gal_us -> pt_us",        8.0                  ; "qualified us gallons to pints")]
+#[test_case("1 pt_imp -> floz_imp",     20.0                 ; "imperial pints to fluid ounces")]
+#[test_case("1 ton -> lb",              2000.0               ; "short tons in default region")]
+#[test_case("1 ton_imp -> kg",          1016.0469088000001   ; "long tons to kilograms")]
+#[test_case("1000 ft_survey -> m",      304.8006096012192    ; "survey feet to meters")]
+#[test_case("1000 ft -> m",             304.8                ; "international feet to meters")]
+pub fn regional_conversion(input: &str, expected: f64) {
+    let mut converter = setup();
+    let actual = converter.convert_from_expression(input);
+
+    assert!(actual.is_ok(), "Returned error {:?}", actual.err());
+    assert_eq!(expected, actual.unwrap().value);
+}
+
+#[test]
+pub fn regional_conversion_default_region() {
+    let mut converter = setup();
+    let actual = converter.convert_from_expression("2 gal -> l").unwrap();
+    assert_eq!(7.571, (actual.value * 1000.0).round() / 1000.0);
+    assert_eq!(
+        vec!["'gal' is ambiguous, using USGallons for region us (could also be ImperialGallons (gal_imp))"],
+        actual.warnings
+    );
+
+    converter.set_region("imp");
+    let actual = converter.convert_from_expression("2 gal -> l").unwrap();
+    assert_eq!(9.09218, actual.value);
+
+    // Qualified abbreviations and units without regional variants don't raise warnings.
+    let actual = converter.convert_from_expression("2 gal_us -> l").unwrap();
+    assert!(actual.warnings.is_empty());
+    let actual = converter.convert_from_expression("12 pt -> px").unwrap();
+    assert_eq!(16.0, actual.value);
+    assert!(actual.warnings.is_empty());
+}
+
+#[test_case("1 Capacity:pt -> ml",      473.176473           ; "qualified pints to milliliters")]
+#[test_case("12 Typography:pt -> px",   16.0                 ; "qualified points to pixels")]
+#[test_case("1 in -> Typography:pt",    72.0                 ; "qualified target unit")]
+#[test_case("1 Data_Rate:MB/s -> Mbps", 8.0                  ; "qualified with a type containing a space")]
+#[test_case("30 mpg -> Fuel_Economy:L/100km", 7.84048611   ; "qualified target with a type containing a space")]
+pub fn qualified_conversion(input: &str, expected: f64) {
+    let mut converter = setup();
+    let actual = converter.convert_from_expression(input);
+
+    assert!(actual.is_ok(), "Returned error {:?}", actual.err());
+    assert_eq!(expected, actual.unwrap().value);
+}
+
+#[test]
+pub fn ambiguous_abbreviation() {
+    let mut converter = setup();
+    let actual = converter.convert_from_expression("1 pt -> kg");
+    assert_eq!(
+        "'pt' is ambiguous, use one of Capacity:pt (ImperialPints or USPints), Typography:pt (Points)",
+        actual.unwrap_err().to_string()
+    );
+
+    let collision = converter
+        .abbreviation_collisions()
+        .iter()
+        .find(|c| c.abbrev == "pt")
+        .unwrap();
+    assert_eq!(vec!["Capacity", "Typography"], collision.unit_types);
+
+    // Lengths repeated in Typography share their abbreviations without being ambiguous.
+    assert!(!converter
+        .abbreviation_collisions()
+        .iter()
+        .any(|c| c.abbrev == "mm" || c.abbrev == "in"));
+    let actual = converter.convert_from_expression("1 in -> mm").unwrap();
+    assert_eq!(25.4, actual.value);
+}
+
+#[test]
+pub fn deny_abbreviation_collisions() {
+    let actual = UnitConverterBuilder::new()
+        .add_unit_definitions(
+            UnitDefinitionSourceToml::from_contents(
+                "units",
+                "[Capacity]\nPints = ['pt']\n[Typography]\nPoints = ['pt']\n",
+            )
+            .load()
+            .unwrap(),
+        )
+        .deny_abbreviation_collisions(true)
+        .build();
+
+    assert_eq!(
+        "Abbreviations are used by several unit types: 'pt' is used by units of types Capacity, Typography",
+        actual.err().unwrap().to_string()
+    );
+}
+
+#[test]
+pub fn case_sensitivity_unit_info() {
+    let converter = setup();
+    assert_eq!(
+        "Kilometers",
+        converter.unit_info("kilometers").unwrap().unit
+    );
+    assert_eq!("Kilometers", converter.unit_info("km").unwrap().unit);
+    assert_eq!(
+        "'KM' is not a valid unit",
+        converter.unit_info("KM").unwrap_err().to_string()
+    );
+
+    let converter = setup_builder()
+        .case_sensitivity(CaseSensitivity::InsensitiveWhenUnambiguous)
+        .build()
+        .unwrap();
+    assert_eq!("Kilometers", converter.unit_info("KM").unwrap().unit);
+    assert_eq!("Megameters", converter.unit_info("Mm").unwrap().unit);
+    assert!(converter.unit_info("MM").is_err());
+}
+
+#[test]
+pub fn case_sensitivity_conversion() {
+    let mut converter = setup();
+    assert!(converter.convert_from_expression("1 KM -> m").is_err());
+
+    let mut converter = setup_builder()
+        .case_sensitivity(CaseSensitivity::InsensitiveWhenUnambiguous)
+        .build()
+        .unwrap();
+    let actual = converter.convert_from_expression("1 KM -> M").unwrap();
+    assert_eq!(1000.0, actual.value);
+    let actual = converter.convert_from_expression("1 Mm -> km").unwrap();
+    assert_eq!(1000.0, actual.value);
+
+    let mut converter = setup_builder()
+        .case_sensitivity(CaseSensitivity::Insensitive)
+        .build()
+        .unwrap();
+    let actual = converter.convert_from_expression("1 Mm -> km");
+    assert_eq!(
+        "'Mm' is ambiguous when ignoring case, use one of Mm (Megameters), mm (Millimeters)",
+        actual.unwrap_err().to_string()
+    );
+}
+
+#[test_case("453406564nm -> km",        0.000453406564       ; "nanometers to kilometers")]
+#[test_case("87pm -> nm",               0.087                ; "picometers to nanometers")]
+#[test_case("25.4 mm -> pc",            6.0                  ; "millimeters to picas")]
+#[test_case("0.3 km -> m",              300.0                ; "decimal value")]
+#[test_case("8 km/L -> L/100km",        12.5                 ; "reciprocal conversion")]
+pub fn exact_conversion(input: &str, expected: f64) {
+    let mut converter = setup_builder().exact_arithmetic(true).build().unwrap();
+    let actual = converter.convert_from_expression(input).unwrap();
+
+    assert_eq!(expected, actual.value);
+    assert!(!actual.approximate);
+}
+
+#[test]
+pub fn exact_conversion_approximate() {
+    let mut converter = setup_builder().exact_arithmetic(true).build().unwrap();
+    let actual = converter.convert_from_expression("-13C -> F").unwrap();
+    assert!(actual.approximate);
+    let actual = converter.convert_from_expression("12 pt -> px").unwrap();
+    assert!(actual.approximate);
+    let actual = converter
+        .convert_from_expression("1 mpg -> L/100km")
+        .unwrap();
+    assert!(actual.approximate);
+    let actual = converter.convert_from_expression("1 turn -> rad").unwrap();
+    assert!(actual.approximate);
+    let actual = converter.convert_from_expression("1 Np -> dB").unwrap();
+    assert!(actual.approximate);
+
+    // Results aren't marked as approximate unless exact arithmetic was requested.
+    let mut converter = setup();
+    let actual = converter.convert_from_expression("-13C -> F").unwrap();
+    assert!(!actual.approximate);
+    let actual = converter
+        .convert_from_expression("453406564nm -> km")
+        .unwrap();
+    assert_eq!(0.0004534065640000001, actual.value);
+}
+
+#[test]
+pub fn exact_conversion_cached() {
+    let mut converter = setup_builder().exact_arithmetic(true).build().unwrap();
+    for _ in 0..2 {
+        let actual = converter
+            .convert_from_expression("453406564nm -> km")
+            .unwrap();
+        assert_eq!(0.000453406564, actual.value);
+        let actual = converter
+            .convert_from_expression("0.000453406564 km -> nm")
+            .unwrap();
+        assert_eq!(453406564.0, actual.value);
+    }
+}
+
+#[test_case("12.5 ± 0.2 cm -> in",      "4.92 ± 0.08"        ; "absolute uncertainty")]
+#[test_case("12.5 ± 4% cm -> mm",       "125 ± 5"            ; "relative uncertainty")]
+#[test_case("20 ± 0.5 C -> F",          "68.0 ± 0.9"         ; "affine expression")]
+#[test_case("1 ± 0.1 W -> dBm",         "30.0 ± 0.4"         ; "logarithmic scale")]
+#[test_case("2 ± 0.1 cups of flour -> g", "281 ± 14"         ; "substance")]
+#[test_case("100 ± 5 EUR -> USD @ 2024-03-01", "108 ± 5"     ; "currency")]
+#[test_case("40 ± 0.1 AWG -> mm",       "0.0799 ± 0.0010"    ; "last point of a table")]
+#[test_case("-3 ± 0.1 AWG -> mm",       "11.68 ± 0.13"       ; "first point of a table")]
+#[test_case("10 ± 0.5 m/s -> Bft",      "5 ± 0"              ; "within a step")]
+#[test_case("10.5 ± 0.5 m/s -> Bft",    "5.0 ± 1.0"          ; "across a step")]
+#[test_case("5 ± 1 Bft -> m/s",         "8 ± 3"              ; "from a step table")]
+pub fn uncertainty_conversion(input: &str, expected: &str) {
+    let mut converter = setup();
+    let actual = converter.convert_from_expression(input);
+
+    assert!(actual.is_ok(), "Returned error {:?}", actual.err());
+    assert_eq!(
+        Some(expected.to_owned()),
+        actual.unwrap().formatted_uncertainty
+    );
+}
+
+#[test]
+pub fn uncertainty_conversion_value() {
+    let mut converter = setup();
+    let actual = converter
+        .convert_from_expression("12.5 ± 0.2 cm -> in")
+        .unwrap();
+    assert_eq!(4.921259842519684, actual.value);
+    assert!((actual.uncertainty.unwrap() - 0.0787401575).abs() < 1e-9);
+
+    let actual = converter.convert_from_expression("12.5 cm -> in").unwrap();
+    assert_eq!(None, actual.uncertainty);
+    assert_eq!(None, actual.formatted_uncertainty);
+}
+
+#[test_case("2 km -> nmi",              1.0,          1     ; "inferred from integer")]
+#[test_case("2.000 km -> nmi",          1.080,        4     ; "inferred from decimal")]
+#[test_case("2.000 in -> mm",           50.80,        4     ; "exact multipliers")]
+#[test_case("2.0000000 m -> in",        78.740157,    8     ; "exact path preferred")]
+#[test_case("2.0000000 mi -> m",        3218.68,      6     ; "approximate multiplier")]
+#[test_case("1.500e3 ft -> mi",         0.2841,       4     ; "exponent")]
+pub fn significant_figures_inferred(input: &str, expected: f64, figures: u32) {
+    let mut converter = setup_builder()
+        .significant_figures(SignificantFigures::Inferred)
+        .build()
+        .unwrap();
+    let actual = converter.convert_from_expression(input).unwrap();
+
+    assert_eq!(Some(expected), actual.rounded);
+    assert_eq!(Some(figures), actual.significant_figures);
+}
+
+#[test_case("1200 km -> m",             "1.2 Mm"              ; "larger prefix")]
+#[test_case("0.5 km -> m",              "500 m"               ; "smaller prefix")]
+#[test_case("2500 ns -> s",             "2.5 μs"              ; "time")]
+#[test_case("2 lea -> mi",              "6 mi"                ; "no prefixes")]
+pub fn format_auto_scale(input: &str, expected: &str) {
+    let mut converter = setup();
+    let formatter = ResultFormatter::new()
+        .auto_scale(true)
+        .unit_style(UnitStyle::Symbol);
+    let actual = converter.convert_from_expression(input).unwrap();
+
+    assert_eq!(
+        expected,
+        formatter.format(&actual, converter.units()).to_string()
+    );
+}
+
+#[test_case("2 km -> nmi",     Notation::Auto,        "1 nautical mile"        ; "rounded value")]
+#[test_case("2.000 km -> nmi", Notation::Auto,        "1.080 nautical miles"   ; "trailing zeros")]
+#[test_case("2.000 km -> mm",  Notation::Engineering, "2.000e6 millimeters"    ; "engineering")]
+#[test_case("255 dec -> hex",  Notation::Auto,        "FF hexadecimal"         ; "exact value")]
+pub fn format_significant_figures(input: &str, notation: Notation, expected: &str) {
+    let mut converter = setup_builder()
+        .significant_figures(SignificantFigures::Inferred)
+        .build()
+        .unwrap();
+    let formatter = ResultFormatter::new().notation(notation);
+    let actual = converter.convert_from_expression(input).unwrap();
+
+    assert_eq!(
+        expected,
+        formatter.format(&actual, converter.units()).to_string()
+    );
+}
+
+#[test_case("1 m -> in",                0.0                 ; "exact chain preferred")]
+#[test_case("1 mi -> km",               5e-6                ; "approximate multiplier")]
+#[test_case("1 ly -> mi",               5.000005e-6         ; "several approximate multipliers")]
+pub fn estimated_relative_error(input: &str, expected: f64) {
+    let mut converter = setup();
+    let actual = converter.convert_from_expression(input).unwrap();
+
+    assert!(
+        (expected - actual.relative_error).abs() < 1e-18,
+        "{}",
+        actual.relative_error
+    );
+
+    // Cached conversions keep the error of the path they replace.
+    let actual = converter.convert_from_expression(input).unwrap();
+    assert!(
+        (expected - actual.relative_error).abs() < 1e-18,
+        "{}",
+        actual.relative_error
+    );
+}
+
+#[test]
+pub fn significant_figures_fixed() {
+    let mut converter = setup_builder()
+        .significant_figures(SignificantFigures::Fixed(3))
+        .build()
+        .unwrap();
+    let actual = converter.convert_from_expression("2 km -> nmi").unwrap();
+    assert_eq!(1.0799136069114472, actual.value);
+    assert_eq!(Some(1.08), actual.rounded);
+
+    // Results aren't rounded by default, even when using approximate multipliers.
+    let mut converter = setup();
+    let actual = converter.convert_from_expression("2 m -> in").unwrap();
+    assert_eq!(None, actual.rounded);
+    assert_eq!(None, actual.significant_figures);
+}